@@ -0,0 +1,723 @@
+use crate::dfa::{syntax_error, ParseError};
+use std::fmt;
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// What neighbours of cells on the edge of a grid are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Boundary {
+    /// the grid wraps around, so opposite edges are neighbours.
+    Wrap,
+    /// cells outside of the grid are always dead.
+    Fixed,
+}
+
+/// Largest number of cells, width times height, of a grid read by Grid::from_rle.
+pub const MAX_RLE_CELLS: usize = 1 << 30;
+
+/// Grid of dead or alive cells, packed 64 cells per u64 word.
+/// Cell x of a row is bit x % 64 of word x / 64, and bits past width are always 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid {
+    width: usize,
+    height: usize,
+    words: usize,
+    cells: Vec<u64>,
+}
+
+impl Grid {
+    /// Grid of dead cells.
+    pub fn new(width: usize, height: usize) -> Self {
+        // one u64 per 64 cells, and one more for the remainder.
+        let words = width / 64 + usize::from(width & 63 != 0);
+        Grid {
+            width,
+            height,
+            words,
+            cells: vec![0; words * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Panics if x or y is out of the grid.
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        assert!(x < self.width && y < self.height, "cell out of the grid");
+        self.row(y)[x / 64] >> (x % 64) & 1 == 1
+    }
+
+    /// Panics if x or y is out of the grid.
+    pub fn set(&mut self, x: usize, y: usize, alive: bool) {
+        assert!(x < self.width && y < self.height, "cell out of the grid");
+        let word = &mut self.cells[y * self.words + x / 64];
+        if alive {
+            *word |= 1 << (x % 64);
+        } else {
+            *word &= !(1 << (x % 64));
+        }
+    }
+
+    /// Number of alive cells.
+    pub fn population(&self) -> usize {
+        self.cells.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    fn row(&self, y: usize) -> &[u64] {
+        &self.cells[y * self.words..(y + 1) * self.words]
+    }
+
+    /// Row above or below y, None if it is outside of a fixed boundary.
+    fn vertical_neighbour(&self, y: usize, up: bool, boundary: Boundary) -> Option<&[u64]> {
+        let next = match (up, boundary) {
+            (true, _) if y > 0 => y - 1,
+            (false, _) if y + 1 < self.height => y + 1,
+            (_, Boundary::Fixed) => return None,
+            (true, Boundary::Wrap) => self.height - 1,
+            (false, Boundary::Wrap) => 0,
+        };
+        Some(self.row(next))
+    }
+
+    /// Mask of the valid bits of the last word of a row.
+    fn last_mask(&self) -> u64 {
+        match self.width % 64 {
+            0 => !0,
+            bits => (1 << bits) - 1,
+        }
+    }
+
+    /// Row whose cell x is the cell x - 1 of row.
+    fn west(&self, row: &[u64], boundary: Boundary) -> Vec<u64> {
+        let mut out = vec![0; self.words];
+        let mut carry = match boundary {
+            Boundary::Wrap if self.width > 0 => {
+                let x = self.width - 1;
+                row[x / 64] >> (x % 64) & 1
+            }
+            _ => 0,
+        };
+        for (o, &w) in out.iter_mut().zip(row) {
+            *o = w << 1 | carry;
+            carry = w >> 63;
+        }
+        if let Some(last) = out.last_mut() {
+            *last &= self.last_mask();
+        }
+        out
+    }
+
+    /// Row whose cell x is the cell x + 1 of row.
+    fn east(&self, row: &[u64], boundary: Boundary) -> Vec<u64> {
+        let mut out = vec![0; self.words];
+        let mut carry = 0;
+        for (o, &w) in out.iter_mut().zip(row).rev() {
+            *o = w >> 1 | carry << 63;
+            carry = w & 1;
+        }
+        if boundary == Boundary::Wrap && self.width > 0 {
+            let x = self.width - 1;
+            out[x / 64] |= (row[0] & 1) << (x % 64);
+        }
+        out
+    }
+
+    /// Read a pattern in the run length encoded format of Life programs.
+    ///
+    /// ```text
+    /// #N Glider
+    /// x = 3, y = 3, rule = B3/S23
+    /// bob$2bo$3o!
+    /// ```
+    ///
+    /// Lines starting with '#' are comments, b is a dead cell, o an alive cell,
+    /// $ ends a row and ! ends the pattern. The rule in the header is optional.
+    pub fn from_rle(text: &str) -> Result<(Grid, Option<LifeRule>), ParseError> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(i, l)| (i + 1, l))
+            .filter(|(_, l)| !l.trim_start().starts_with('#') && !l.trim().is_empty());
+        let (line, header) = match lines.next() {
+            Some(header) => header,
+            None => return syntax_error(1, 1, "missing header".to_string()),
+        };
+
+        let mut width = None;
+        let mut height = None;
+        let mut rule = None;
+        let mut column = 1;
+        for field in header.split(',') {
+            let start = column;
+            column += field.chars().count() + 1;
+            let column = start;
+            let mut parts = field.splitn(2, '=').map(str::trim);
+            let (key, value) = match (parts.next(), parts.next()) {
+                (Some(key), Some(value)) => (key, value),
+                _ => {
+                    return syntax_error(
+                        line,
+                        column,
+                        format!("expected key = value, found '{}'", field.trim()),
+                    )
+                }
+            };
+            let size = || {
+                value.parse::<usize>().or_else(|_| {
+                    syntax_error(
+                        line,
+                        column,
+                        format!("expected a number, found '{}'", value),
+                    )
+                })
+            };
+            match key {
+                "x" => width = Some(size()?),
+                "y" => height = Some(size()?),
+                "rule" => {
+                    rule = Some(value.parse::<LifeRule>().or_else(|_| {
+                        syntax_error(line, column, format!("invalid rule '{}'", value))
+                    })?)
+                }
+                _ => return syntax_error(line, column, format!("unknown key '{}'", key)),
+            }
+        }
+        let missing = |name: &str| ParseError::Syntax {
+            line,
+            column: 1,
+            message: format!("missing {}", name),
+        };
+        let width = width.ok_or_else(|| missing("x"))?;
+        let height = height.ok_or_else(|| missing("y"))?;
+        match width.checked_mul(height) {
+            Some(cells) if cells <= MAX_RLE_CELLS => {}
+            _ => {
+                return syntax_error(
+                    line,
+                    1,
+                    format!("pattern of {} by {} cells is too large", width, height),
+                )
+            }
+        }
+        let mut grid = Grid::new(width, height);
+
+        let (mut x, mut y): (usize, usize) = (0, 0);
+        let mut count: Option<usize> = None;
+        let mut end = None;
+        'body: for (line, content) in lines {
+            for (i, c) in content.chars().enumerate() {
+                let column = i + 1;
+                if let Some(digit) = c.to_digit(10) {
+                    count = count
+                        .unwrap_or(0)
+                        .checked_mul(10)
+                        .and_then(|n| n.checked_add(digit as usize));
+                    if count.is_none() {
+                        return syntax_error(line, column, "run count is too large".to_string());
+                    }
+                    continue;
+                }
+                if c.is_whitespace() {
+                    continue;
+                }
+                let run = count.take().unwrap_or(1);
+                match c {
+                    'b' => x = x.saturating_add(run),
+                    'o' => {
+                        if x.checked_add(run)
+                            .filter(|&end| end <= grid.width)
+                            .is_none()
+                            || y >= grid.height
+                        {
+                            return syntax_error(
+                                line,
+                                column,
+                                "pattern is larger than x and y".to_string(),
+                            );
+                        }
+                        for dx in 0..run {
+                            grid.set(x + dx, y, true);
+                        }
+                        x += run;
+                    }
+                    '$' => {
+                        x = 0;
+                        y = y.saturating_add(run);
+                    }
+                    '!' => {
+                        end = Some(());
+                        break 'body;
+                    }
+                    _ => return syntax_error(line, column, format!("unexpected '{}'", c)),
+                }
+            }
+        }
+        if end.is_none() {
+            return syntax_error(text.lines().count() + 1, 1, "missing '!'".to_string());
+        }
+        Ok((grid, rule))
+    }
+
+    /// Write this grid in the run length encoded format read by from_rle,
+    /// with lines of at most 70 characters after the header.
+    pub fn to_rle(&self, rule: Option<&LifeRule>) -> String {
+        let mut out = format!("x = {}, y = {}", self.width, self.height);
+        if let Some(rule) = rule {
+            write!(out, ", rule = {}", rule).unwrap();
+        }
+        out.push('\n');
+
+        let token = |count: usize, c: char| match count {
+            1 => c.to_string(),
+            n => format!("{}{}", n, c),
+        };
+        let mut tokens = Vec::new();
+        let mut rows = 0;
+        for y in 0..self.height {
+            if y > 0 {
+                rows += 1;
+            }
+            let mut x = 0;
+            while x < self.width {
+                let alive = self.get(x, y);
+                let start = x;
+                while x < self.width && self.get(x, y) == alive {
+                    x += 1;
+                }
+                // trailing dead cells of a row are implied.
+                if !alive && x == self.width {
+                    break;
+                }
+                if rows > 0 {
+                    tokens.push(token(rows, '$'));
+                    rows = 0;
+                }
+                tokens.push(token(x - start, if alive { 'o' } else { 'b' }));
+            }
+        }
+        tokens.push("!".to_string());
+
+        let mut length = 0;
+        for t in tokens {
+            if length + t.len() > 70 {
+                out.push('\n');
+                length = 0;
+            }
+            length += t.len();
+            out.push_str(&t);
+        }
+        out.push('\n');
+        out
+    }
+}
+
+/// Written with # for alive cells and . for dead cells, one line per row.
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                write!(f, "{}", if self.get(x, y) { '#' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Wolfram's elementary cellular automaton.
+/// Every row of a grid is a separate one dimensional world, and the next
+/// state of a cell is bit 4 l + 2 c + r of rule for left, center and right cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elementary {
+    rule: u8,
+    boundary: Boundary,
+}
+
+impl Elementary {
+    pub fn new(rule: u8, boundary: Boundary) -> Self {
+        Elementary { rule, boundary }
+    }
+
+    pub fn rule(&self) -> u8 {
+        self.rule
+    }
+
+    /// Next generation of every row of grid.
+    pub fn step(&self, grid: &Grid) -> Grid {
+        let mut next = Grid::new(grid.width, grid.height);
+        for y in 0..grid.height {
+            let center = grid.row(y);
+            let west = grid.west(center, self.boundary);
+            let east = grid.east(center, self.boundary);
+            let out = &mut next.cells[y * grid.words..(y + 1) * grid.words];
+            for (i, o) in out.iter_mut().enumerate() {
+                // evaluate the rule on 64 cells at once, one neighbourhood pattern at a time.
+                for pattern in 0..8 {
+                    if self.rule >> pattern & 1 == 0 {
+                        continue;
+                    }
+                    let pick = |bit: u8, w: u64| if pattern >> bit & 1 == 1 { w } else { !w };
+                    *o |= pick(2, west[i]) & pick(1, center[i]) & pick(0, east[i]);
+                }
+            }
+            if let Some(last) = out.last_mut() {
+                *last &= grid.last_mask();
+            }
+        }
+        next
+    }
+
+    /// Space time diagram of a single row world: row t of the result
+    /// is generation t, starting from generation 0 given by row.
+    pub fn history(&self, row: &[bool], generations: usize) -> Grid {
+        let mut current = Grid::new(row.len(), 1);
+        for (x, &alive) in row.iter().enumerate() {
+            current.set(x, 0, alive);
+        }
+        let mut history = Grid::new(row.len(), generations + 1);
+        for t in 0..=generations {
+            history.cells[t * current.words..(t + 1) * current.words]
+                .copy_from_slice(&current.cells);
+            current = self.step(&current);
+        }
+        history
+    }
+}
+
+/// Life-like rule in B/S notation, for example B3/S23 for Conway's Game of Life.
+/// Bit n of birth and survival is set when a cell with n alive neighbours
+/// is born or survives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LifeRule {
+    birth: u16,
+    survival: u16,
+}
+
+impl LifeRule {
+    /// B3/S23.
+    pub fn conway() -> Self {
+        LifeRule {
+            birth: 1 << 3,
+            survival: 1 << 2 | 1 << 3,
+        }
+    }
+
+    /// Whether a dead cell with neighbours alive neighbours is born.
+    pub fn is_birth(&self, neighbours: u32) -> bool {
+        self.birth >> neighbours & 1 == 1
+    }
+
+    /// Whether an alive cell with neighbours alive neighbours survives.
+    pub fn is_survival(&self, neighbours: u32) -> bool {
+        self.survival >> neighbours & 1 == 1
+    }
+}
+
+/// Error of parsing a LifeRule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleError(String);
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid rule '{}', expected B/S notation like B3/S23",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for RuleError {}
+
+/// Parses B3/S23, with the parts in any order and case.
+impl FromStr for LifeRule {
+    type Err = RuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || RuleError(s.to_string());
+        let mut birth = None;
+        let mut survival = None;
+        for part in s.trim().split('/') {
+            let mut chars = part.chars();
+            let target = match chars.next() {
+                Some('B') | Some('b') => &mut birth,
+                Some('S') | Some('s') => &mut survival,
+                _ => return Err(error()),
+            };
+            if target.is_some() {
+                return Err(error());
+            }
+            let mut bits = 0u16;
+            for c in chars {
+                match c.to_digit(10) {
+                    Some(n) if n <= 8 => bits |= 1 << n,
+                    _ => return Err(error()),
+                }
+            }
+            *target = Some(bits);
+        }
+        Ok(LifeRule {
+            birth: birth.ok_or_else(error)?,
+            survival: survival.ok_or_else(error)?,
+        })
+    }
+}
+
+impl fmt::Display for LifeRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "B")?;
+        for n in (0..9).filter(|&n| self.is_birth(n)) {
+            write!(f, "{}", n)?;
+        }
+        write!(f, "/S")?;
+        for n in (0..9).filter(|&n| self.is_survival(n)) {
+            write!(f, "{}", n)?;
+        }
+        Ok(())
+    }
+}
+
+/// Two dimensional life-like cellular automaton with the Moore neighbourhood.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Life {
+    rule: LifeRule,
+    boundary: Boundary,
+}
+
+impl Life {
+    pub fn new(rule: LifeRule, boundary: Boundary) -> Self {
+        Life { rule, boundary }
+    }
+
+    pub fn rule(&self) -> LifeRule {
+        self.rule
+    }
+
+    /// Next generation of grid.
+    /// Neighbours of 64 cells are counted at once by adding the eight shifted rows
+    /// bit by bit into four bit planes.
+    pub fn step(&self, grid: &Grid) -> Grid {
+        let mut next = Grid::new(grid.width, grid.height);
+        let empty = vec![0; grid.words];
+        for y in 0..grid.height {
+            let center = grid.row(y);
+            let above = grid
+                .vertical_neighbour(y, true, self.boundary)
+                .unwrap_or(&empty);
+            let below = grid
+                .vertical_neighbour(y, false, self.boundary)
+                .unwrap_or(&empty);
+            let neighbours = [
+                grid.west(above, self.boundary),
+                above.to_vec(),
+                grid.east(above, self.boundary),
+                grid.west(center, self.boundary),
+                grid.east(center, self.boundary),
+                grid.west(below, self.boundary),
+                below.to_vec(),
+                grid.east(below, self.boundary),
+            ];
+            let out = &mut next.cells[y * grid.words..(y + 1) * grid.words];
+            for (i, o) in out.iter_mut().enumerate() {
+                let mut planes = [0u64; 4];
+                for row in &neighbours {
+                    let mut carry = row[i];
+                    for plane in planes.iter_mut() {
+                        let overflow = *plane & carry;
+                        *plane ^= carry;
+                        carry = overflow;
+                    }
+                }
+                for n in 0..9 {
+                    let birth = self.rule.is_birth(n);
+                    let survival = self.rule.is_survival(n);
+                    if !birth && !survival {
+                        continue;
+                    }
+                    let count = planes.iter().enumerate().fold(!0, |acc, (bit, &p)| {
+                        acc & if n >> bit & 1 == 1 { p } else { !p }
+                    });
+                    if birth {
+                        *o |= count & !center[i];
+                    }
+                    if survival {
+                        *o |= count & center[i];
+                    }
+                }
+            }
+            if let Some(last) = out.last_mut() {
+                *last &= grid.last_mask();
+            }
+        }
+        next
+    }
+
+    /// Grid after generations steps.
+    pub fn run(&self, grid: &Grid, generations: usize) -> Grid {
+        let mut current = grid.clone();
+        for _ in 0..generations {
+            current = self.step(&current);
+        }
+        current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GLIDER: &str = "#N Glider\nx = 3, y = 3, rule = B3/S23\nbob$2bo$3o!\n";
+
+    fn glider_at(width: usize, height: usize, dx: usize, dy: usize) -> Grid {
+        let mut grid = Grid::new(width, height);
+        for &(x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)].iter() {
+            grid.set((x + dx) % width, (y + dy) % height, true);
+        }
+        grid
+    }
+
+    #[test]
+    fn test_elementary_rule_90() {
+        let mut row = vec![false; 9];
+        row[4] = true;
+        let history = Elementary::new(90, Boundary::Fixed).history(&row, 4);
+        assert_eq!(
+            history.to_string(),
+            "....#....\n...#.#...\n..#...#..\n.#.#.#.#.\n#.......#\n"
+        );
+    }
+
+    #[test]
+    fn test_elementary_boundaries() {
+        // rule 170 shifts every cell one to the left.
+        let mut row = vec![false; 70];
+        row[0] = true;
+        let wrap = Elementary::new(170, Boundary::Wrap).history(&row, 1);
+        assert!(wrap.get(69, 1));
+        assert_eq!(wrap.population(), 2);
+        let fixed = Elementary::new(170, Boundary::Fixed).history(&row, 1);
+        assert_eq!(fixed.population(), 1);
+    }
+
+    #[test]
+    fn test_elementary_rule_30_matches_definition() {
+        let rule = 30u8;
+        let mut row = vec![false; 130];
+        row[65] = true;
+        let history = Elementary::new(rule, Boundary::Wrap).history(&row, 60);
+        for t in 0..60 {
+            for x in 0..130 {
+                let cell = |x: usize| history.get(x % 130, t) as u8;
+                let pattern = cell(x + 129) << 2 | cell(x) << 1 | cell(x + 1);
+                assert_eq!(history.get(x, t + 1), rule >> pattern & 1 == 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rule_notation() {
+        let rule: LifeRule = "B36/S23".parse().unwrap();
+        assert_eq!(rule.to_string(), "B36/S23");
+        assert_eq!("s23/b3".parse::<LifeRule>(), Ok(LifeRule::conway()));
+        assert!("B9/S23".parse::<LifeRule>().is_err());
+        assert!("B3".parse::<LifeRule>().is_err());
+    }
+
+    #[test]
+    fn test_blinker_and_block() {
+        let life = Life::new(LifeRule::conway(), Boundary::Fixed);
+        let mut grid = Grid::new(5, 5);
+        for x in 1..4 {
+            grid.set(x, 2, true);
+        }
+        let next = life.step(&grid);
+        assert_eq!(next.to_string(), ".....\n..#..\n..#..\n..#..\n.....\n");
+        assert_eq!(life.step(&next), grid);
+
+        let mut block = Grid::new(2, 2);
+        for &(x, y) in [(0, 0), (0, 1), (1, 0), (1, 1)].iter() {
+            block.set(x, y, true);
+        }
+        assert_eq!(life.run(&block, 5), block);
+    }
+
+    #[test]
+    fn test_glider_on_torus() {
+        // on a wrapping grid, a glider moves one cell diagonally every 4 generations.
+        let life = Life::new(LifeRule::conway(), Boundary::Wrap);
+        let grid = glider_at(70, 6, 0, 0);
+        assert_eq!(life.run(&grid, 4), glider_at(70, 6, 1, 1));
+        assert_eq!(life.run(&grid, 24), glider_at(70, 6, 6, 0));
+        assert_eq!(life.run(&grid, 4 * 70 * 3), grid);
+
+        // with fixed boundaries it becomes a block in the corner.
+        let fixed = Life::new(LifeRule::conway(), Boundary::Fixed);
+        let end = fixed.run(&glider_at(6, 6, 0, 0), 40);
+        assert_eq!(end.population(), 4);
+        assert!(end.get(5, 5));
+    }
+
+    #[test]
+    fn test_rle_round_trip() {
+        let (grid, rule) = Grid::from_rle(GLIDER).unwrap();
+        assert_eq!(grid, glider_at(3, 3, 0, 0));
+        assert_eq!(rule, Some(LifeRule::conway()));
+        let rle = grid.to_rle(rule.as_ref());
+        assert_eq!(rle, "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n");
+        assert_eq!(Grid::from_rle(&rle).unwrap(), (grid, rule));
+
+        let mut sparse = Grid::new(100, 10);
+        sparse.set(99, 0, true);
+        sparse.set(0, 9, true);
+        let rle = sparse.to_rle(None);
+        assert_eq!(rle, "x = 100, y = 10\n99bo9$o!\n");
+        assert_eq!(Grid::from_rle(&rle).unwrap().0, sparse);
+    }
+
+    #[test]
+    fn test_rle_errors() {
+        assert_eq!(
+            Grid::from_rle("x = 2, y = 1\n3o!").unwrap_err(),
+            ParseError::Syntax {
+                line: 2,
+                column: 2,
+                message: "pattern is larger than x and y".to_string()
+            }
+        );
+        assert_eq!(
+            Grid::from_rle("x = 2, y = 1\nozo!").unwrap_err(),
+            ParseError::Syntax {
+                line: 2,
+                column: 2,
+                message: "unexpected 'z'".to_string()
+            }
+        );
+        assert!(Grid::from_rle("x = 2, y = 1\noo").is_err());
+        assert!(Grid::from_rle("x = 2\no!").is_err());
+        assert_eq!(
+            Grid::from_rle("x = 2, y = 1\n99999999999999999999999o!").unwrap_err(),
+            ParseError::Syntax {
+                line: 2,
+                column: 20,
+                message: "run count is too large".to_string()
+            }
+        );
+        assert_eq!(
+            Grid::from_rle("x = 18446744073709551615, y = 2\n!").unwrap_err(),
+            ParseError::Syntax {
+                line: 1,
+                column: 1,
+                message: "pattern of 18446744073709551615 by 2 cells is too large".to_string()
+            }
+        );
+        assert!(Grid::from_rle("x = 100000, y = 100000\n!").is_err());
+        assert!(
+            Grid::from_rle("x = 2, y = 2\n18446744073709551615b$18446744073709551615$o!").is_err()
+        );
+    }
+}
@@ -1,12 +1,40 @@
 use std::collections::HashSet;
 use std::iter::FromIterator;
 
+mod analysis;
+mod codegen;
+mod compact;
+mod equivalence;
+mod error;
+mod format;
+mod labeled;
+mod minimize;
+mod observer;
+mod partial;
+mod product;
+mod runner;
+mod search;
+mod synchronizing;
+
+pub use analysis::Words;
+pub use codegen::CodegenStyle;
+pub use compact::CompactDfa;
+pub use error::DfaError;
+pub use format::ParseError;
+pub(crate) use format::{check_duplicate, number, numbers, single_number, syntax_error, tokens};
+pub use labeled::{LabeledDfa, LabeledDfaBuilder};
+pub use observer::{NoObserver, Observer, Trace};
+pub use partial::PartialDfa;
+pub use runner::{DfaRunner, RunnerSnapshot};
+pub use search::MatchKind;
+
 /// Deterministic Finite Automaton
 /// For simplisity, we express states and alphabets as u64 integers,
 /// For example, if states is 2 and alphabet is 4,
 /// set of state and alphabet is [0, 1] and [0, 1, 2, 3].
 /// transition function is expressed as two dimentional Vec of u64.
 /// transition function for state 0 and alphabet 2 is store in transition_function[0][2].
+#[derive(Debug, Clone)]
 pub struct Dfa {
     states: u64,
     alphabets: u64,
@@ -16,61 +44,147 @@ pub struct Dfa {
 }
 
 impl Dfa {
+    /// Build a Dfa after validating that the table has states rows and alphabets columns,
+    /// and that every transition target, the initial state and the accept states are
+    /// valid states.
     pub fn new(
         states: u64,
         alphabets: u64,
         transition_function: Vec<Vec<u64>>,
         initial_state: u64,
         accept_states: Vec<u64>,
-    ) -> Result<Self, ()> {
-        // check transition_function's row and column is correct number.
-        if transition_function.len() == states as usize
-            && transition_function
-                .iter()
-                .all(|v| v.len() == alphabets as usize)
-        {
-            Ok(Dfa {
-                states,
-                alphabets,
-                transition_function,
-                initial_state,
-                accept_states: HashSet::from_iter(accept_states),
-            })
-        } else {
-            Err(())
+    ) -> Result<Self, DfaError> {
+        validate_table(states, alphabets, &transition_function, initial_state)?;
+        if let Some(&state) = accept_states.iter().find(|&&q| q >= states) {
+            return Err(DfaError::AcceptStateOutOfRange(state));
         }
+
+        Ok(Dfa {
+            states,
+            alphabets,
+            transition_function,
+            initial_state,
+            accept_states: HashSet::from_iter(accept_states),
+        })
+    }
+
+    pub fn states(&self) -> u64 {
+        self.states
     }
 
-    fn transition(&self, current: u64, alphabet: u64) -> Result<u64, ()> {
-        if current < self.states && alphabet < self.alphabets {
-            let next = self.transition_function[current as usize][alphabet as usize];
-            println!("transition from: {}, to: {}", current, next);
-            Ok(next)
+    pub fn alphabets(&self) -> u64 {
+        self.alphabets
+    }
+
+    pub fn initial_state(&self) -> u64 {
+        self.initial_state
+    }
+
+    pub fn is_accept_state(&self, state: u64) -> bool {
+        self.accept_states.contains(&state)
+    }
+
+    /// State reached from state on alphabet, None if either is out of range.
+    pub fn next_state(&self, state: u64, alphabet: u64) -> Option<u64> {
+        if state < self.states && alphabet < self.alphabets {
+            Some(self.transition(state, alphabet))
         } else {
-            Err(())
+            None
         }
     }
 
-    pub fn execute(&self, input: Vec<u64>) -> Result<bool, ()> {
-        if !input.iter().all(|a| a < &self.alphabets) {
-            return Err(());
+    fn transition(&self, current: u64, alphabet: u64) -> u64 {
+        self.transition_function[current as usize][alphabet as usize]
+    }
+
+    /// Check that every alphabet of input is in range.
+    fn check_input(&self, input: &[u64]) -> Result<(), DfaError> {
+        match input.iter().position(|a| a >= &self.alphabets) {
+            Some(position) => Err(DfaError::InvalidInput {
+                position,
+                alphabet: input[position],
+            }),
+            None => Ok(()),
         }
+    }
 
-        let res = input
-            .iter()
-            .fold(Ok(self.initial_state), |state, alphabet| {
-                self.transition(state.unwrap(), *alphabet)
-            })
-            .unwrap();
+    pub fn execute(&self, input: Vec<u64>) -> Result<bool, DfaError> {
+        self.execute_with(input, &mut NoObserver)
+    }
+
+    /// Same as execute, but observer is notified of every transition taken.
+    pub fn execute_with<O: Observer>(
+        &self,
+        input: Vec<u64>,
+        observer: &mut O,
+    ) -> Result<bool, DfaError> {
+        self.check_input(&input)?;
+
+        let res = input.iter().fold(self.initial_state, |state, alphabet| {
+            let next = self.transition(state, *alphabet);
+            observer.on_transition(state, *alphabet, next);
+            next
+        });
         Ok(self.accept_states.contains(&res))
     }
 
     pub fn is_language(&self, input: Vec<u64>) -> bool {
-        match self.execute(input) {
-            Ok(res) => res,
-            _ => false,
+        self.execute(input).unwrap_or(false)
+    }
+}
+
+/// Check that table has states rows and alphabets columns.
+pub(crate) fn check_shape<T>(
+    states: u64,
+    alphabets: u64,
+    table: &[Vec<T>],
+) -> Result<(), DfaError> {
+    if table.len() != states as usize {
+        return Err(DfaError::WrongRowCount {
+            expected: states,
+            found: table.len(),
+        });
+    }
+    for (state, row) in table.iter().enumerate() {
+        if row.len() != alphabets as usize {
+            return Err(DfaError::WrongColumnCount {
+                state: state as u64,
+                expected: alphabets,
+                found: row.len(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Check the shape of a deterministic transition function,
+/// and that every target and the initial state are valid states.
+/// Targets are u64, or Option<u64> where None is a missing transition.
+pub(crate) fn validate_table<T>(
+    states: u64,
+    alphabets: u64,
+    transition_function: &[Vec<T>],
+    initial_state: u64,
+) -> Result<(), DfaError>
+where
+    T: Copy + Into<Option<u64>>,
+{
+    check_shape(states, alphabets, transition_function)?;
+    for (state, row) in transition_function.iter().enumerate() {
+        for (alphabet, &next) in row.iter().enumerate() {
+            if let Some(target) = next.into().filter(|&target| target >= states) {
+                return Err(DfaError::TargetOutOfRange {
+                    state: state as u64,
+                    alphabet: alphabet as u64,
+                    target,
+                });
+            }
         }
     }
+    if initial_state >= states {
+        return Err(DfaError::InitialStateOutOfRange(initial_state));
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -122,6 +236,77 @@ mod tests {
         assert!(dfa.is_language(input));
     }
 
+    #[test]
+    fn test_invalid_input() {
+        let transition_function = vec![vec![1, 0], vec![0, 1]];
+        let dfa = Dfa::new(2, 2, transition_function, 0, vec![0]).unwrap();
+
+        let input = vec![0, 1, 2, 0];
+        assert_eq!(
+            dfa.execute(input),
+            Err(DfaError::InvalidInput {
+                position: 2,
+                alphabet: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_new_wrong_row_count() {
+        let transition_function = vec![vec![1, 0]];
+        assert_eq!(
+            Dfa::new(2, 2, transition_function, 0, vec![0]).unwrap_err(),
+            DfaError::WrongRowCount {
+                expected: 2,
+                found: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_new_wrong_column_count() {
+        let transition_function = vec![vec![1, 0], vec![0]];
+        assert_eq!(
+            Dfa::new(2, 2, transition_function, 0, vec![0]).unwrap_err(),
+            DfaError::WrongColumnCount {
+                state: 1,
+                expected: 2,
+                found: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_new_target_out_of_range() {
+        let transition_function = vec![vec![1, 0], vec![0, 2]];
+        assert_eq!(
+            Dfa::new(2, 2, transition_function, 0, vec![0]).unwrap_err(),
+            DfaError::TargetOutOfRange {
+                state: 1,
+                alphabet: 1,
+                target: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_new_initial_state_out_of_range() {
+        let transition_function = vec![vec![1, 0], vec![0, 1]];
+        assert_eq!(
+            Dfa::new(2, 2, transition_function, 2, vec![0]).unwrap_err(),
+            DfaError::InitialStateOutOfRange(2)
+        );
+    }
+
+    #[test]
+    fn test_new_accept_state_out_of_range() {
+        let transition_function = vec![vec![1, 0], vec![0, 1]];
+        assert_eq!(
+            Dfa::new(2, 2, transition_function, 0, vec![0, 3]).unwrap_err(),
+            DfaError::AcceptStateOutOfRange(3)
+        );
+    }
+
     #[test]
     fn test_non_language() {
         let transition_function = vec![vec![1, 0], vec![0, 1]];
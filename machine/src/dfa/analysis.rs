@@ -0,0 +1,294 @@
+use super::Dfa;
+use std::collections::VecDeque;
+
+/// Iterator over words accepted by a Dfa in shortlex order,
+/// that is shorter words first and words of the same length in lexicographic order.
+/// Created by Dfa::words.
+#[derive(Debug, Clone)]
+pub struct Words<'a> {
+    dfa: &'a Dfa,
+    // exact[k][q] is true if an accept state is reached from q with exactly k alphabets.
+    exact: Vec<Vec<bool>>,
+    // longest accepted word, None if the language is infinite.
+    max_length: Option<usize>,
+    length: usize,
+    started: bool,
+    // (state, next alphabet to try) for each prefix of the current word.
+    stack: Vec<(u64, u64)>,
+    word: Vec<u64>,
+}
+
+impl Dfa {
+    /// Whether this Dfa accepts no input at all.
+    pub fn is_empty(&self) -> bool {
+        let reachable = self.reachable_states();
+        !self.accept_states.iter().any(|&q| reachable[q as usize])
+    }
+
+    /// Whether this Dfa accepts only a finite number of inputs.
+    /// The language is infinite if and only if there is a cycle through
+    /// states which are both reachable and can reach an accept state.
+    pub fn is_finite(&self) -> bool {
+        let reachable = self.reachable_states();
+        let live = self.live_states();
+        let useful: Vec<bool> = (0..self.states as usize)
+            .map(|q| reachable[q] && live[q])
+            .collect();
+
+        // iterative depth first search, 0: unvisited, 1: on stack, 2: done.
+        let mut color = vec![0u8; self.states as usize];
+        for start in 0..self.states as usize {
+            if !useful[start] || color[start] != 0 {
+                continue;
+            }
+            color[start] = 1;
+            let mut stack = vec![(start, 0)];
+            while let Some((q, i)) = stack.pop() {
+                if i == self.alphabets as usize {
+                    color[q] = 2;
+                    continue;
+                }
+                stack.push((q, i + 1));
+                let next = self.transition_function[q][i] as usize;
+                if !useful[next] {
+                    continue;
+                }
+                match color[next] {
+                    0 => {
+                        color[next] = 1;
+                        stack.push((next, 0));
+                    }
+                    1 => return false,
+                    _ => {}
+                }
+            }
+        }
+        true
+    }
+
+    pub fn is_infinite(&self) -> bool {
+        !self.is_finite()
+    }
+
+    /// Shortest accepted input, the smallest in lexicographic order among
+    /// inputs of the same length. None if the language is empty.
+    pub fn shortest_word(&self) -> Option<Vec<u64>> {
+        let n = self.states as usize;
+        let mut parent: Vec<Option<(u64, u64)>> = vec![None; n];
+        let mut visited = vec![false; n];
+        let mut queue = VecDeque::new();
+        visited[self.initial_state as usize] = true;
+        queue.push_back(self.initial_state);
+
+        while let Some(q) = queue.pop_front() {
+            if self.accept_states.contains(&q) {
+                let mut word = Vec::new();
+                let mut current = q;
+                while let Some((previous, alphabet)) = parent[current as usize] {
+                    word.push(alphabet);
+                    current = previous;
+                }
+                word.reverse();
+                return Some(word);
+            }
+            for (a, &next) in self.transition_function[q as usize].iter().enumerate() {
+                if !visited[next as usize] {
+                    visited[next as usize] = true;
+                    parent[next as usize] = Some((q, a as u64));
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Number of accepted inputs of exactly length n.
+    /// None if the number does not fit in u128.
+    pub fn count_words(&self, n: usize) -> Option<u128> {
+        // counts[q] is the number of inputs leading from q to an accept state.
+        let mut counts: Vec<u128> = (0..self.states)
+            .map(|q| self.accept_states.contains(&q) as u128)
+            .collect();
+        for _ in 0..n {
+            counts = self
+                .transition_function
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .try_fold(0u128, |sum, &next| sum.checked_add(counts[next as usize]))
+                })
+                .collect::<Option<_>>()?;
+        }
+        Some(counts[self.initial_state as usize])
+    }
+
+    /// Lazy iterator over every accepted input in shortlex order.
+    /// The iterator never ends if the language is infinite.
+    pub fn words(&self) -> Words<'_> {
+        let max_length = if self.is_finite() {
+            // a word longer than the number of states would go through a cycle.
+            Some(self.states as usize)
+        } else {
+            None
+        };
+        let accepting = (0..self.states)
+            .map(|q| self.accept_states.contains(&q))
+            .collect();
+        Words {
+            dfa: self,
+            exact: vec![accepting],
+            max_length,
+            length: 0,
+            started: false,
+            stack: Vec::new(),
+            word: Vec::new(),
+        }
+    }
+}
+
+impl<'a> Words<'a> {
+    fn exact_up_to(&mut self, k: usize) {
+        while self.exact.len() <= k {
+            let last = self.exact.last().unwrap();
+            let next = self
+                .dfa
+                .transition_function
+                .iter()
+                .map(|row| row.iter().any(|&q| last[q as usize]))
+                .collect();
+            self.exact.push(next);
+        }
+    }
+}
+
+impl<'a> Iterator for Words<'a> {
+    type Item = Vec<u64>;
+
+    fn next(&mut self) -> Option<Vec<u64>> {
+        loop {
+            let (state, next) = match self.stack.last() {
+                Some(&frame) => frame,
+                None => {
+                    // every word of the current length is done, go to the next length.
+                    if self.started {
+                        self.length += 1;
+                    }
+                    self.started = true;
+                    if let Some(max) = self.max_length {
+                        if self.length > max {
+                            return None;
+                        }
+                    }
+                    self.exact_up_to(self.length);
+                    let initial = self.dfa.initial_state;
+                    if self.exact[self.length][initial as usize] {
+                        self.stack.push((initial, 0));
+                    }
+                    continue;
+                }
+            };
+
+            let depth = self.stack.len() - 1;
+            if depth == self.length {
+                let word = self.word.clone();
+                self.stack.pop();
+                self.word.pop();
+                return Some(word);
+            }
+
+            let remaining = self.length - depth - 1;
+            let row = &self.dfa.transition_function[state as usize];
+            let found = (next..self.dfa.alphabets)
+                .find(|&a| self.exact[remaining][row[a as usize] as usize]);
+            match found {
+                Some(a) => {
+                    self.stack.last_mut().unwrap().1 = a + 1;
+                    self.stack.push((row[a as usize], 0));
+                    self.word.push(a);
+                }
+                None => {
+                    self.stack.pop();
+                    self.word.pop();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // accepts inputs with even number of 1.
+    fn even_ones() -> Dfa {
+        Dfa::new(2, 2, vec![vec![0, 1], vec![1, 0]], 0, vec![0]).unwrap()
+    }
+
+    // accepts exactly 0, 0 1 and 1 1 over alphabets {0, 1}, state 4 is a sink.
+    fn finite() -> Dfa {
+        Dfa::new(
+            5,
+            2,
+            vec![vec![1, 2], vec![4, 3], vec![4, 3], vec![4, 4], vec![4, 4]],
+            0,
+            vec![1, 3],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_is_empty() {
+        assert!(!even_ones().is_empty());
+        // accept state 1 is unreachable.
+        let dfa = Dfa::new(2, 1, vec![vec![0], vec![1]], 0, vec![1]).unwrap();
+        assert!(dfa.is_empty());
+        assert_eq!(dfa.shortest_word(), None);
+        assert_eq!(dfa.words().next(), None);
+    }
+
+    #[test]
+    fn test_is_finite() {
+        assert!(even_ones().is_infinite());
+        assert!(finite().is_finite());
+    }
+
+    #[test]
+    fn test_shortest_word() {
+        assert_eq!(even_ones().shortest_word(), Some(vec![]));
+        assert_eq!(finite().shortest_word(), Some(vec![0]));
+        assert_eq!(even_ones().complement().shortest_word(), Some(vec![1]));
+    }
+
+    #[test]
+    fn test_count_words() {
+        let dfa = even_ones();
+        assert_eq!(dfa.count_words(0), Some(1));
+        assert_eq!(dfa.count_words(3), Some(4));
+        assert_eq!(dfa.count_words(10), Some(512));
+        assert_eq!(finite().count_words(2), Some(2));
+        assert_eq!(finite().count_words(3), Some(0));
+        assert_eq!(dfa.count_words(200), None);
+    }
+
+    #[test]
+    fn test_words_finite() {
+        let words: Vec<Vec<u64>> = finite().words().collect();
+        assert_eq!(words, vec![vec![0], vec![0, 1], vec![1, 1]]);
+    }
+
+    #[test]
+    fn test_words_infinite() {
+        let words: Vec<Vec<u64>> = even_ones().words().take(6).collect();
+        assert_eq!(
+            words,
+            vec![
+                vec![],
+                vec![0],
+                vec![0, 0],
+                vec![1, 1],
+                vec![0, 0, 0],
+                vec![0, 1, 1]
+            ]
+        );
+    }
+}
@@ -0,0 +1,224 @@
+use super::Dfa;
+use std::fmt::Write;
+
+/// Shape of the Rust code generated by Dfa::to_rust.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodegenStyle {
+    /// nested match on the state and the alphabet, good for small automata.
+    Match,
+    /// static transition table using the smallest integer type for states.
+    Table,
+}
+
+impl Dfa {
+    /// Rust source of a standalone function
+    /// `pub fn name(input: &[u64]) -> Option<bool>` deciding the language of this Dfa,
+    /// returning None when input has an alphabet out of range like execute does.
+    /// The code has no dependencies, so it can be written by build.rs
+    /// into OUT_DIR and included with include!. name must be a valid identifier.
+    pub fn to_rust(&self, name: &str, style: CodegenStyle) -> String {
+        let mut out = String::new();
+        writeln!(
+            out,
+            "/// Generated from a Dfa with {} states and {} alphabets.",
+            self.states, self.alphabets
+        )
+        .unwrap();
+        writeln!(out, "pub fn {}(input: &[u64]) -> Option<bool> {{", name).unwrap();
+        match style {
+            CodegenStyle::Match => self.write_match(&mut out),
+            CodegenStyle::Table => self.write_table(&mut out),
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    fn write_match(&self, out: &mut String) {
+        if self.alphabets == 0 {
+            write_no_alphabets(out, self.initial_state);
+        } else {
+            writeln!(out, "    let mut state: u64 = {};", self.initial_state).unwrap();
+            out.push_str("    for &a in input {\n        state = match state {\n");
+            for (q, row) in self.transition_function.iter().enumerate() {
+                writeln!(out, "            {} => match a {{", q).unwrap();
+                // alphabets going to the same state share an arm.
+                let mut targets: Vec<u64> = row.clone();
+                targets.sort_unstable();
+                targets.dedup();
+                for target in targets {
+                    let alphabets: Vec<u64> = (0..self.alphabets)
+                        .filter(|&a| row[a as usize] == target)
+                        .collect();
+                    writeln!(
+                        out,
+                        "                {} => {},",
+                        patterns(&alphabets),
+                        target
+                    )
+                    .unwrap();
+                }
+                out.push_str("                _ => return None,\n            },\n");
+            }
+            out.push_str("            _ => unreachable!(),\n        };\n    }\n");
+        }
+        out.push_str("    Some(match state {\n");
+        let accept_states = self.sorted_accept_states();
+        if !accept_states.is_empty() {
+            writeln!(out, "        {} => true,", patterns(&accept_states)).unwrap();
+        }
+        out.push_str("        _ => false,\n    })\n");
+    }
+
+    fn write_table(&self, out: &mut String) {
+        let state_type = match self.states {
+            n if n <= 1 << 8 => "u8",
+            n if n <= 1 << 16 => "u16",
+            n if n <= 1 << 32 => "u32",
+            _ => "u64",
+        };
+        let accept: Vec<&str> = (0..self.states)
+            .map(|q| {
+                if self.is_accept_state(q) {
+                    "true"
+                } else {
+                    "false"
+                }
+            })
+            .collect();
+        writeln!(
+            out,
+            "    static ACCEPT: [bool; {}] = [{}];",
+            self.states,
+            accept.join(", ")
+        )
+        .unwrap();
+        if self.alphabets == 0 {
+            write_no_alphabets(out, self.initial_state);
+            out.push_str("    Some(ACCEPT[state])\n");
+            return;
+        }
+        writeln!(
+            out,
+            "    static TRANSITIONS: [[{}; {}]; {}] = [",
+            state_type, self.alphabets, self.states
+        )
+        .unwrap();
+        for row in &self.transition_function {
+            let row: Vec<String> = row.iter().map(|q| q.to_string()).collect();
+            writeln!(out, "        [{}],", row.join(", ")).unwrap();
+        }
+        out.push_str("    ];\n");
+        writeln!(out, "    let mut state = {}usize;", self.initial_state).unwrap();
+        out.push_str("    for &a in input {\n");
+        writeln!(
+            out,
+            "        if a >= {} {{\n            return None;\n        }}",
+            self.alphabets
+        )
+        .unwrap();
+        out.push_str("        state = TRANSITIONS[state][a as usize] as usize;\n    }\n");
+        out.push_str("    Some(ACCEPT[state])\n");
+    }
+}
+
+/// Without alphabets only the empty input is valid and the state never changes.
+fn write_no_alphabets(out: &mut String, initial_state: u64) {
+    out.push_str("    if !input.is_empty() {\n        return None;\n    }\n");
+    writeln!(out, "    let state = {}usize;", initial_state).unwrap();
+}
+
+/// Match pattern of sorted values, with consecutive runs written as ranges.
+fn patterns(values: &[u64]) -> String {
+    let mut parts = Vec::new();
+    let mut i = 0;
+    while i < values.len() {
+        let mut j = i;
+        while j + 1 < values.len() && values[j + 1] == values[j] + 1 {
+            j += 1;
+        }
+        if j > i {
+            parts.push(format!("{}..={}", values[i], values[j]));
+        } else {
+            parts.push(values[i].to_string());
+        }
+        i = j + 1;
+    }
+    parts.join(" | ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::{random_word, DfaGenerator, Rng};
+    use std::process::Command;
+
+    #[test]
+    fn test_patterns() {
+        assert_eq!(patterns(&[0, 1, 2, 4, 6, 7]), "0..=2 | 4 | 6..=7");
+        assert_eq!(patterns(&[3]), "3");
+    }
+
+    #[test]
+    fn test_match_source() {
+        let dfa = Dfa::new(2, 3, vec![vec![1, 1, 0], vec![1, 0, 0]], 0, vec![1]).unwrap();
+        let source = dfa.to_rust("ends_in_small", CodegenStyle::Match);
+        assert!(source.contains("pub fn ends_in_small(input: &[u64]) -> Option<bool> {"));
+        assert!(source.contains("0..=1 => 1,"));
+        assert!(source.contains("1..=2 => 0,"));
+    }
+
+    // compile the generated functions with rustc and compare them with execute.
+    fn check_compiled(style: CodegenStyle, seed: u64) {
+        let mut rng = Rng::new(seed);
+        let mut source = String::new();
+        let mut main = String::from("fn main() {\n");
+        let mut expected = String::new();
+        for i in 0..10 {
+            let states = 1 + rng.below(12);
+            let alphabets = rng.below(4);
+            let dfa = DfaGenerator::new(states, alphabets)
+                .reachable(i % 2 == 0 && alphabets > 0)
+                .generate(&mut rng);
+            source.push_str(&dfa.to_rust(&format!("dfa_{}", i), style));
+            for _ in 0..30 {
+                // one more alphabet than the Dfa knows, to check invalid inputs too.
+                let input = random_word(&mut rng, alphabets + 1, 10);
+                writeln!(main, "    println!(\"{{:?}}\", dfa_{}(&{:?}));", i, input).unwrap();
+                writeln!(expected, "{:?}", dfa.execute(input).ok()).unwrap();
+            }
+        }
+        main.push_str("}\n");
+        source.push_str(&main);
+
+        let dir = std::env::temp_dir().join(format!(
+            "machine_codegen_{:?}_{}",
+            style,
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("generated.rs");
+        let binary = dir.join("generated");
+        std::fs::write(&path, source).unwrap();
+        let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+        let status = Command::new(rustc)
+            .args(["--edition", "2018", "-D", "warnings", "-o"])
+            .arg(&binary)
+            .arg(&path)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let output = Command::new(&binary).output().unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_match_compiled() {
+        check_compiled(CodegenStyle::Match, 19);
+    }
+
+    #[test]
+    fn test_table_compiled() {
+        check_compiled(CodegenStyle::Table, 20);
+    }
+}
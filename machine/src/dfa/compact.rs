@@ -0,0 +1,233 @@
+use super::{Dfa, DfaError};
+use std::collections::HashMap;
+
+/// Read only Dfa with a cache friendly transition table.
+///
+/// Compared to Dfa it
+/// - merges alphabets with identical columns into alphabet classes,
+/// - stores every transition in one flat Vec, where the row of a state
+///   starts at state * classes, and stores that premultiplied offset instead
+///   of the state id so a transition is a single index operation,
+/// - uses the smallest unsigned integer type which holds every offset.
+///
+/// It accepts exactly the same inputs as the Dfa it is built from.
+#[derive(Debug, Clone)]
+pub struct CompactDfa {
+    table: Table,
+    // class of each alphabet.
+    classes: Vec<usize>,
+    // number of alphabet classes, the length of one row.
+    stride: usize,
+    // premultiplied initial state.
+    initial: usize,
+    // accept[state] without premultiplication.
+    accept: Vec<bool>,
+}
+
+#[derive(Debug, Clone)]
+enum Table {
+    U8(Vec<u8>),
+    U16(Vec<u16>),
+    U32(Vec<u32>),
+    U64(Vec<u64>),
+}
+
+/// Unsigned integer type used for the entries of the table.
+trait StateId: Copy {
+    fn from_usize(v: usize) -> Self;
+    fn to_usize(self) -> usize;
+}
+
+macro_rules! state_id {
+    ($($t:ty),*) => {
+        $(
+            impl StateId for $t {
+                #[inline(always)]
+                fn from_usize(v: usize) -> Self {
+                    v as $t
+                }
+
+                #[inline(always)]
+                fn to_usize(self) -> usize {
+                    self as usize
+                }
+            }
+        )*
+    };
+}
+
+state_id!(u8, u16, u32, u64);
+
+impl Dfa {
+    /// Build the compact representation of this Dfa.
+    pub fn compact(&self) -> CompactDfa {
+        // alphabets with the same column share a class, numbered in order of appearance.
+        let mut class_of_column: HashMap<Vec<u64>, usize> = HashMap::new();
+        let mut representatives = Vec::new();
+        let classes = (0..self.alphabets as usize)
+            .map(|a| {
+                let column: Vec<u64> = self.transition_function.iter().map(|row| row[a]).collect();
+                *class_of_column.entry(column).or_insert_with(|| {
+                    representatives.push(a);
+                    representatives.len() - 1
+                })
+            })
+            .collect();
+        let stride = representatives.len().max(1);
+
+        let offsets = self.transition_function.iter().flat_map(|row| {
+            representatives
+                .iter()
+                .map(move |&a| row[a] as usize * stride)
+        });
+        let max_offset = (self.states as usize - 1) * stride;
+        let table = if max_offset <= u8::MAX as usize {
+            Table::U8(offsets.map(u8::from_usize).collect())
+        } else if max_offset <= u16::MAX as usize {
+            Table::U16(offsets.map(u16::from_usize).collect())
+        } else if max_offset <= u32::MAX as usize {
+            Table::U32(offsets.map(u32::from_usize).collect())
+        } else {
+            Table::U64(offsets.map(u64::from_usize).collect())
+        };
+
+        CompactDfa {
+            table,
+            classes,
+            stride,
+            initial: self.initial_state as usize * stride,
+            accept: (0..self.states)
+                .map(|q| self.accept_states.contains(&q))
+                .collect(),
+        }
+    }
+}
+
+impl CompactDfa {
+    /// Number of alphabet classes after merging alphabets with identical columns.
+    pub fn alphabet_classes(&self) -> usize {
+        self.stride
+    }
+
+    /// Size of one entry of the table in bytes.
+    pub fn state_size(&self) -> usize {
+        match self.table {
+            Table::U8(_) => 1,
+            Table::U16(_) => 2,
+            Table::U32(_) => 4,
+            Table::U64(_) => 8,
+        }
+    }
+
+    pub fn execute(&self, input: Vec<u64>) -> Result<bool, DfaError> {
+        if let Some(position) = input.iter().position(|&a| a >= self.classes.len() as u64) {
+            return Err(DfaError::InvalidInput {
+                position,
+                alphabet: input[position],
+            });
+        }
+        Ok(self.run(input.into_iter().map(|a| a as usize)))
+    }
+
+    /// Execute on bytes, each byte being one alphabet.
+    pub fn execute_bytes(&self, input: &[u8]) -> Result<bool, DfaError> {
+        if let Some(position) = input.iter().position(|&a| a as usize >= self.classes.len()) {
+            return Err(DfaError::InvalidInput {
+                position,
+                alphabet: input[position] as u64,
+            });
+        }
+        Ok(self.run(input.iter().map(|&a| a as usize)))
+    }
+
+    pub fn is_language(&self, input: Vec<u64>) -> bool {
+        self.execute(input).unwrap_or(false)
+    }
+
+    fn run<I>(&self, input: I) -> bool
+    where
+        I: Iterator<Item = usize>,
+    {
+        let end = match &self.table {
+            Table::U8(table) => self.run_table(table, input),
+            Table::U16(table) => self.run_table(table, input),
+            Table::U32(table) => self.run_table(table, input),
+            Table::U64(table) => self.run_table(table, input),
+        };
+        self.accept[end / self.stride]
+    }
+
+    #[inline(always)]
+    fn run_table<S, I>(&self, table: &[S], input: I) -> usize
+    where
+        S: StateId,
+        I: Iterator<Item = usize>,
+    {
+        let classes = &self.classes[..];
+        input.fold(self.initial, |state, a| {
+            table[state + classes[a]].to_usize()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alphabet_classes() {
+        // alphabets 0 and 2 behave the same, so do 1 and 3.
+        let dfa = Dfa::new(2, 4, vec![vec![1, 0, 1, 0], vec![0, 0, 0, 0]], 0, vec![1]).unwrap();
+        let compact = dfa.compact();
+        assert_eq!(compact.alphabet_classes(), 2);
+        assert_eq!(compact.state_size(), 1);
+        for input in [
+            vec![],
+            vec![0],
+            vec![2],
+            vec![0, 1],
+            vec![1, 2],
+            vec![3, 3, 2],
+        ]
+        .iter()
+        {
+            assert_eq!(compact.execute(input.clone()), dfa.execute(input.clone()));
+        }
+        assert_eq!(
+            compact.execute(vec![0, 4]),
+            Err(DfaError::InvalidInput {
+                position: 1,
+                alphabet: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_wider_state_type() {
+        // alphabet 0 counts modulo 300 and alphabet 1 keeps the state.
+        let transition_function = (0..300).map(|q| vec![(q + 1) % 300, q]).collect();
+        let dfa = Dfa::new(300, 2, transition_function, 0, vec![0]).unwrap();
+        let compact = dfa.compact();
+        assert_eq!(compact.state_size(), 2);
+        assert!(compact.is_language(vec![0; 600]));
+        assert!(!compact.is_language(vec![0; 599]));
+        assert!(compact.is_language(vec![1, 1]));
+    }
+
+    #[test]
+    fn test_execute_bytes() {
+        // accepts bytes ending with b'a'.
+        let transition_function = (0..2)
+            .map(|_| {
+                (0..256)
+                    .map(|b| if b == b'a' as u64 { 1 } else { 0 })
+                    .collect()
+            })
+            .collect();
+        let dfa = Dfa::new(2, 256, transition_function, 0, vec![1]).unwrap();
+        let compact = dfa.compact();
+        assert_eq!(compact.alphabet_classes(), 2);
+        assert_eq!(compact.execute_bytes(b"banana"), Ok(true));
+        assert_eq!(compact.execute_bytes(b"bananas"), Ok(false));
+    }
+}
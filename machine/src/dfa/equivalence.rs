@@ -0,0 +1,131 @@
+use super::Dfa;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+/// Pair of states of two Dfas, None is the dead state.
+type Pair = (Option<u64>, Option<u64>);
+
+impl Dfa {
+    /// Check that self and other accept the same language.
+    /// On failure, returns the shortest input accepted by exactly one of them.
+    /// Among inputs of the same length, the smallest in lexicographic order is returned.
+    pub fn equivalent(&self, other: &Dfa) -> Result<(), Vec<u64>> {
+        match self.distinguishing_word(other, |a, b| a != b) {
+            Some(word) => Err(word),
+            None => Ok(()),
+        }
+    }
+
+    /// Check that every input accepted by self is accepted by other.
+    /// On failure, returns the shortest input accepted by self but rejected by other.
+    pub fn is_subset_of(&self, other: &Dfa) -> Result<(), Vec<u64>> {
+        match self.distinguishing_word(other, |a, b| a && !b) {
+            Some(word) => Err(word),
+            None => Ok(()),
+        }
+    }
+
+    /// Breadth first search over pairs of states for the shortest input
+    /// where differs(self accepts, other accepts) is true.
+    /// Alphabets out of range for one of the Dfas lead it to a dead state,
+    /// represented as None, the same way is_language rejects such inputs.
+    fn distinguishing_word<F>(&self, other: &Dfa, differs: F) -> Option<Vec<u64>>
+    where
+        F: Fn(bool, bool) -> bool,
+    {
+        let alphabets = self.alphabets.max(other.alphabets);
+        let step = |dfa: &Dfa, state: Option<u64>, alphabet: u64| match state {
+            Some(q) if alphabet < dfa.alphabets => {
+                Some(dfa.transition_function[q as usize][alphabet as usize])
+            }
+            _ => None,
+        };
+        let accepts = |dfa: &Dfa, state: Option<u64>| match state {
+            Some(q) => dfa.accept_states.contains(&q),
+            None => false,
+        };
+
+        let initial = (Some(self.initial_state), Some(other.initial_state));
+        // parent of each visited pair and the alphabet used to reach it.
+        let mut parent: HashMap<Pair, Option<(Pair, u64)>> = HashMap::new();
+        let mut queue = VecDeque::new();
+        parent.insert(initial, None);
+        queue.push_back(initial);
+
+        while let Some(pair) = queue.pop_front() {
+            if differs(accepts(self, pair.0), accepts(other, pair.1)) {
+                let mut word = Vec::new();
+                let mut current = pair;
+                while let Some(&Some((previous, alphabet))) = parent.get(&current) {
+                    word.push(alphabet);
+                    current = previous;
+                }
+                word.reverse();
+                return Some(word);
+            }
+
+            for alphabet in 0..alphabets {
+                let next = (step(self, pair.0, alphabet), step(other, pair.1, alphabet));
+                if let Entry::Vacant(entry) = parent.entry(next) {
+                    entry.insert(Some((pair, alphabet)));
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equivalent() {
+        let dfa = Dfa::new(2, 2, vec![vec![1, 0], vec![0, 1]], 0, vec![0]).unwrap();
+        // same language with a redundant copy of state 0.
+        let other = Dfa::new(
+            3,
+            2,
+            vec![vec![1, 2], vec![2, 1], vec![1, 0]],
+            0,
+            vec![0, 2],
+        )
+        .unwrap();
+        assert_eq!(dfa.equivalent(&other), Ok(()));
+        assert_eq!(other.equivalent(&dfa), Ok(()));
+    }
+
+    #[test]
+    fn test_not_equivalent_shortest_word() {
+        // accepts inputs with even number of 0.
+        let dfa = Dfa::new(2, 2, vec![vec![1, 0], vec![0, 1]], 0, vec![0]).unwrap();
+        // accepts inputs with even number of 0 and not ending with 1 1.
+        let other = Dfa::new(
+            4,
+            2,
+            vec![vec![1, 2], vec![0, 1], vec![1, 3], vec![1, 3]],
+            0,
+            vec![0, 2],
+        )
+        .unwrap();
+        assert_eq!(dfa.equivalent(&other), Err(vec![1, 1]));
+        assert_eq!(other.is_subset_of(&dfa), Ok(()));
+        assert_eq!(dfa.is_subset_of(&other), Err(vec![1, 1]));
+    }
+
+    #[test]
+    fn test_equivalent_empty_word() {
+        let dfa = Dfa::new(1, 2, vec![vec![0, 0]], 0, vec![0]).unwrap();
+        let other = Dfa::new(1, 2, vec![vec![0, 0]], 0, vec![]).unwrap();
+        assert_eq!(dfa.equivalent(&other), Err(vec![]));
+    }
+
+    #[test]
+    fn test_equivalent_different_alphabets() {
+        let dfa = Dfa::new(1, 2, vec![vec![0, 0]], 0, vec![0]).unwrap();
+        let other = Dfa::new(1, 3, vec![vec![0, 0, 0]], 0, vec![0]).unwrap();
+        assert_eq!(dfa.is_subset_of(&other), Ok(()));
+        assert_eq!(other.is_subset_of(&dfa), Err(vec![2]));
+    }
+}
@@ -0,0 +1,127 @@
+use std::error::Error;
+use std::fmt;
+
+/// Errors returned while building or executing a Dfa.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DfaError {
+    /// transition_function does not have one row per state.
+    WrongRowCount { expected: u64, found: usize },
+    /// output_function of a Moore machine does not have one output per state.
+    WrongOutputCount { expected: u64, found: usize },
+    /// a row of transition_function does not have one column per alphabet.
+    WrongColumnCount {
+        state: u64,
+        expected: u64,
+        found: usize,
+    },
+    /// a row of a Mealy machine's output_function does not have one column per alphabet.
+    WrongOutputColumnCount {
+        state: u64,
+        expected: u64,
+        found: usize,
+    },
+    /// a transition goes to a state which does not exist.
+    TargetOutOfRange {
+        state: u64,
+        alphabet: u64,
+        target: u64,
+    },
+    /// initial state is not a valid state.
+    InitialStateOutOfRange(u64),
+    /// one of accept states is not a valid state.
+    AcceptStateOutOfRange(u64),
+    /// input contains an alphabet out of range at position.
+    InvalidInput { position: usize, alphabet: u64 },
+    /// input contains a symbol without a label at position.
+    UnknownSymbol { position: usize },
+    /// no initial state is given to the builder.
+    MissingInitialState,
+    /// no transition is given from state on alphabet.
+    MissingTransition { state: u64, alphabet: u64 },
+    /// more than one transition is given from state on alphabet.
+    DuplicateTransition { state: u64, alphabet: u64 },
+    /// a transition or a set of states refers to a state which does not exist.
+    StateOutOfRange(u64),
+    /// a transition reads an alphabet which does not exist.
+    AlphabetOutOfRange(u64),
+    /// two Dfas combined together do not share the same alphabets.
+    AlphabetMismatch { left: u64, right: u64 },
+}
+
+impl fmt::Display for DfaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DfaError::WrongRowCount { expected, found } => write!(
+                f,
+                "transition function has {} rows, expected {}",
+                found, expected
+            ),
+            DfaError::WrongOutputCount { expected, found } => write!(
+                f,
+                "output function has {} outputs, expected {}",
+                found, expected
+            ),
+            DfaError::WrongColumnCount {
+                state,
+                expected,
+                found,
+            } => write!(
+                f,
+                "transition function for state {} has {} columns, expected {}",
+                state, found, expected
+            ),
+            DfaError::WrongOutputColumnCount {
+                state,
+                expected,
+                found,
+            } => write!(
+                f,
+                "output function for state {} has {} columns, expected {}",
+                state, found, expected
+            ),
+            DfaError::TargetOutOfRange {
+                state,
+                alphabet,
+                target,
+            } => write!(
+                f,
+                "transition from state {} on alphabet {} goes to unknown state {}",
+                state, alphabet, target
+            ),
+            DfaError::InitialStateOutOfRange(state) => {
+                write!(f, "initial state {} is out of range", state)
+            }
+            DfaError::AcceptStateOutOfRange(state) => {
+                write!(f, "accept state {} is out of range", state)
+            }
+            DfaError::InvalidInput { position, alphabet } => write!(
+                f,
+                "invalid alphabet {} at position {} of input",
+                alphabet, position
+            ),
+            DfaError::UnknownSymbol { position } => {
+                write!(f, "unknown symbol at position {} of input", position)
+            }
+            DfaError::MissingInitialState => write!(f, "initial state is not given"),
+            DfaError::MissingTransition { state, alphabet } => write!(
+                f,
+                "no transition from state {} on alphabet {}",
+                state, alphabet
+            ),
+            DfaError::DuplicateTransition { state, alphabet } => write!(
+                f,
+                "more than one transition from state {} on alphabet {}",
+                state, alphabet
+            ),
+            DfaError::StateOutOfRange(state) => write!(f, "state {} is out of range", state),
+            DfaError::AlphabetOutOfRange(alphabet) => {
+                write!(f, "alphabet {} is out of range", alphabet)
+            }
+            DfaError::AlphabetMismatch { left, right } => {
+                write!(f, "alphabets do not match: {} and {}", left, right)
+            }
+        }
+    }
+}
+
+impl Error for DfaError {}
@@ -0,0 +1,636 @@
+use super::{Dfa, DfaError};
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::fmt::Write;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Errors returned while reading a Dfa or another machine from text or JSON.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// input is malformed at line and column, both starting from 1.
+    Syntax {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// input is well formed but does not describe a valid Dfa.
+    Invalid(DfaError),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Syntax {
+                line,
+                column,
+                message,
+            } => write!(f, "{}:{}: {}", line, column, message),
+            ParseError::Invalid(err) => write!(f, "invalid machine: {}", err),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+impl From<DfaError> for ParseError {
+    fn from(err: DfaError) -> Self {
+        ParseError::Invalid(err)
+    }
+}
+
+pub(crate) fn syntax_error<T>(
+    line: usize,
+    column: usize,
+    message: String,
+) -> Result<T, ParseError> {
+    Err(ParseError::Syntax {
+        line,
+        column,
+        message,
+    })
+}
+
+impl Dfa {
+    /// Read a Dfa from the text table format.
+    ///
+    /// ```text
+    /// # comments start with '#' and blank lines are ignored.
+    /// states 2
+    /// alphabets 2
+    /// initial 0
+    /// accept 0
+    /// transitions
+    /// 1 0
+    /// 0 1
+    /// ```
+    ///
+    /// states, alphabets and initial take one number, accept takes zero or more.
+    /// They can come in any order before transitions, and each at most once.
+    /// Every line after transitions is the row of one state, in order, listing
+    /// next states for alphabet 0, 1, ... A row without alphabets is written as -.
+    pub fn from_text(text: &str) -> Result<Dfa, ParseError> {
+        let mut states = None;
+        let mut alphabets = None;
+        let mut initial_state = None;
+        let mut accept_states = None;
+        let mut transition_function: Option<Vec<Vec<u64>>> = None;
+        let mut seen = HashSet::new();
+
+        for (i, raw) in text.lines().enumerate() {
+            let line = i + 1;
+            let content = match raw.find('#') {
+                Some(end) => &raw[..end],
+                None => raw,
+            };
+            let mut tokens = tokens(content).peekable();
+            let (column, keyword) = match tokens.peek() {
+                Some(&token) => token,
+                None => continue,
+            };
+
+            if let Some(rows) = transition_function.as_mut() {
+                if keyword == "-" {
+                    tokens.next();
+                    if let Some((column, token)) = tokens.next() {
+                        return syntax_error(
+                            line,
+                            column,
+                            format!("unexpected '{}' after -", token),
+                        );
+                    }
+                    rows.push(Vec::new());
+                } else {
+                    rows.push(numbers(line, tokens)?);
+                }
+                continue;
+            }
+            tokens.next();
+            check_duplicate(&mut seen, line, column, keyword)?;
+            match keyword {
+                "states" => states = Some(single_number(line, column, keyword, tokens)?),
+                "alphabets" => alphabets = Some(single_number(line, column, keyword, tokens)?),
+                "initial" => initial_state = Some(single_number(line, column, keyword, tokens)?),
+                "accept" => accept_states = Some(numbers(line, tokens)?),
+                "transitions" => {
+                    if let Some((column, token)) = tokens.next() {
+                        return syntax_error(
+                            line,
+                            column,
+                            format!("unexpected '{}' after transitions", token),
+                        );
+                    }
+                    transition_function = Some(Vec::new());
+                }
+                _ => {
+                    return syntax_error(line, column, format!("unknown keyword '{}'", keyword));
+                }
+            }
+        }
+
+        let end = text.lines().count() + 1;
+        let missing = |name: &str| ParseError::Syntax {
+            line: end,
+            column: 1,
+            message: format!("missing {}", name),
+        };
+        Ok(Dfa::new(
+            states.ok_or_else(|| missing("states"))?,
+            alphabets.ok_or_else(|| missing("alphabets"))?,
+            transition_function.ok_or_else(|| missing("transitions"))?,
+            initial_state.ok_or_else(|| missing("initial"))?,
+            accept_states.unwrap_or_default(),
+        )?)
+    }
+
+    /// Write this Dfa in the text table format read by from_text.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "states {}", self.states).unwrap();
+        writeln!(out, "alphabets {}", self.alphabets).unwrap();
+        writeln!(out, "initial {}", self.initial_state).unwrap();
+        out.push_str("accept");
+        for q in self.sorted_accept_states() {
+            write!(out, " {}", q).unwrap();
+        }
+        out.push_str("\ntransitions\n");
+        for row in &self.transition_function {
+            if row.is_empty() {
+                // a blank line would be skipped when reading.
+                out.push_str("-\n");
+                continue;
+            }
+            let row: Vec<String> = row.iter().map(|q| q.to_string()).collect();
+            writeln!(out, "{}", row.join(" ")).unwrap();
+        }
+        out
+    }
+
+    /// Read a Dfa from JSON of the form
+    ///
+    /// ```json
+    /// {
+    ///   "states": 2,
+    ///   "alphabets": 2,
+    ///   "initial_state": 0,
+    ///   "accept_states": [0],
+    ///   "transition_function": [[1, 0], [0, 1]]
+    /// }
+    /// ```
+    ///
+    /// Every key is required, keys can come in any order and unknown keys are errors.
+    pub fn from_json(json: &str) -> Result<Dfa, ParseError> {
+        let mut parser = JsonParser::new(json);
+        let mut states = None;
+        let mut alphabets = None;
+        let mut initial_state = None;
+        let mut accept_states = None;
+        let mut transition_function = None;
+
+        let (line, column) = parser.position();
+        parser.expect('{')?;
+        if !parser.eat('}') {
+            loop {
+                let (key_line, key_column) = parser.position();
+                let key = parser.string()?;
+                parser.expect(':')?;
+                let duplicate = match key.as_str() {
+                    "states" => states.replace(parser.number()?).is_some(),
+                    "alphabets" => alphabets.replace(parser.number()?).is_some(),
+                    "initial_state" => initial_state.replace(parser.number()?).is_some(),
+                    "accept_states" => accept_states.replace(parser.numbers()?).is_some(),
+                    "transition_function" => {
+                        let mut rows = Vec::new();
+                        parser.expect('[')?;
+                        if !parser.eat(']') {
+                            loop {
+                                rows.push(parser.numbers()?);
+                                if !parser.eat(',') {
+                                    parser.expect(']')?;
+                                    break;
+                                }
+                            }
+                        }
+                        transition_function.replace(rows).is_some()
+                    }
+                    _ => {
+                        return syntax_error(
+                            key_line,
+                            key_column,
+                            format!("unknown key \"{}\"", key),
+                        );
+                    }
+                };
+                if duplicate {
+                    return syntax_error(
+                        key_line,
+                        key_column,
+                        format!("duplicate key \"{}\"", key),
+                    );
+                }
+                if !parser.eat(',') {
+                    parser.expect('}')?;
+                    break;
+                }
+            }
+        }
+        parser.end()?;
+
+        let missing = |name: &str| ParseError::Syntax {
+            line,
+            column,
+            message: format!("missing key \"{}\"", name),
+        };
+        Ok(Dfa::new(
+            states.ok_or_else(|| missing("states"))?,
+            alphabets.ok_or_else(|| missing("alphabets"))?,
+            transition_function.ok_or_else(|| missing("transition_function"))?,
+            initial_state.ok_or_else(|| missing("initial_state"))?,
+            accept_states.ok_or_else(|| missing("accept_states"))?,
+        )?)
+    }
+
+    /// Write this Dfa as JSON read by from_json.
+    pub fn to_json(&self) -> String {
+        let list = |v: &[u64]| {
+            let v: Vec<String> = v.iter().map(|q| q.to_string()).collect();
+            format!("[{}]", v.join(", "))
+        };
+        let rows: Vec<String> = self
+            .transition_function
+            .iter()
+            .map(|row| format!("    {}", list(row)))
+            .collect();
+        format!(
+            "{{\n  \"states\": {},\n  \"alphabets\": {},\n  \"initial_state\": {},\n  \"accept_states\": {},\n  \"transition_function\": [\n{}\n  ]\n}}\n",
+            self.states,
+            self.alphabets,
+            self.initial_state,
+            list(&self.sorted_accept_states()),
+            rows.join(",\n")
+        )
+    }
+
+    pub(super) fn sorted_accept_states(&self) -> Vec<u64> {
+        let mut accept_states: Vec<u64> = self.accept_states.iter().copied().collect();
+        accept_states.sort_unstable();
+        accept_states
+    }
+}
+
+/// Fail if keyword was already given in a text format.
+pub(crate) fn check_duplicate<'a>(
+    seen: &mut HashSet<&'a str>,
+    line: usize,
+    column: usize,
+    keyword: &'a str,
+) -> Result<(), ParseError> {
+    if seen.insert(keyword) {
+        Ok(())
+    } else {
+        syntax_error(line, column, format!("duplicate '{}'", keyword))
+    }
+}
+
+/// Whitespace separated tokens of a line with their column.
+pub(crate) fn tokens(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut column = 0;
+    line.split(|c: char| c.is_whitespace())
+        .filter_map(move |token| {
+            let start = column + 1;
+            column += token.chars().count() + 1;
+            if token.is_empty() {
+                None
+            } else {
+                Some((start, token))
+            }
+        })
+}
+
+pub(crate) fn number(line: usize, column: usize, token: &str) -> Result<u64, ParseError> {
+    token.parse().or_else(|_| {
+        syntax_error(
+            line,
+            column,
+            format!("expected a number, found '{}'", token),
+        )
+    })
+}
+
+pub(crate) fn numbers<'a, I>(line: usize, tokens: I) -> Result<Vec<u64>, ParseError>
+where
+    I: Iterator<Item = (usize, &'a str)>,
+{
+    tokens
+        .map(|(column, token)| number(line, column, token))
+        .collect()
+}
+
+pub(crate) fn single_number<'a, I>(
+    line: usize,
+    column: usize,
+    keyword: &str,
+    mut tokens: I,
+) -> Result<u64, ParseError>
+where
+    I: Iterator<Item = (usize, &'a str)>,
+{
+    let value = match tokens.next() {
+        Some((column, token)) => number(line, column, token)?,
+        None => return syntax_error(line, column, format!("{} needs a number", keyword)),
+    };
+    match tokens.next() {
+        Some((column, token)) => syntax_error(
+            line,
+            column,
+            format!("unexpected '{}' after {}", token, keyword),
+        ),
+        None => Ok(value),
+    }
+}
+
+/// Just enough of a JSON reader for the Dfa schema, keeping track of position.
+struct JsonParser<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn new(json: &'a str) -> Self {
+        JsonParser {
+            chars: json.chars().peekable(),
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn position(&mut self) -> (usize, usize) {
+        self.skip_whitespace();
+        (self.line, self.column)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.chars.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            self.bump();
+        }
+    }
+
+    fn error<T>(&mut self, expected: &str) -> Result<T, ParseError> {
+        let (line, column) = self.position();
+        let found = match self.chars.peek() {
+            Some(c) => format!("'{}'", c),
+            None => "end of input".to_string(),
+        };
+        syntax_error(
+            line,
+            column,
+            format!("expected {}, found {}", expected, found),
+        )
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&expected) {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        if self.eat(expected) {
+            Ok(())
+        } else {
+            self.error(&format!("'{}'", expected))
+        }
+    }
+
+    fn end(&mut self) -> Result<(), ParseError> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            None => Ok(()),
+            Some(_) => self.error("end of input"),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        self.expect('"')?;
+        let mut s = String::new();
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(s),
+                Some('\\') => match self.bump() {
+                    Some(c @ '"') | Some(c @ '\\') | Some(c @ '/') => s.push(c),
+                    _ => {
+                        return syntax_error(
+                            self.line,
+                            self.column - 1,
+                            "unsupported escape sequence".to_string(),
+                        );
+                    }
+                },
+                Some(c) => s.push(c),
+                None => return self.error("'\"'"),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<u64, ParseError> {
+        let (line, column) = self.position();
+        let mut digits = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if !c.is_ascii_digit() {
+                break;
+            }
+            digits.push(c);
+            self.bump();
+        }
+        if digits.is_empty() {
+            return self.error("a number");
+        }
+        number(line, column, &digits)
+    }
+
+    fn numbers(&mut self) -> Result<Vec<u64>, ParseError> {
+        let mut v = Vec::new();
+        self.expect('[')?;
+        if self.eat(']') {
+            return Ok(v);
+        }
+        loop {
+            v.push(self.number()?);
+            if !self.eat(',') {
+                self.expect(']')?;
+                return Ok(v);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dfa() -> Dfa {
+        Dfa::new(
+            3,
+            2,
+            vec![vec![1, 0], vec![2, 0], vec![2, 2]],
+            0,
+            vec![2, 1],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let text = dfa().to_text();
+        assert_eq!(
+            text,
+            "states 3\nalphabets 2\ninitial 0\naccept 1 2\ntransitions\n1 0\n2 0\n2 2\n"
+        );
+        let parsed = Dfa::from_text(&text).unwrap();
+        assert_eq!(parsed.transition_function, dfa().transition_function);
+        assert_eq!(parsed.equivalent(&dfa()), Ok(()));
+    }
+
+    #[test]
+    fn test_text_no_alphabets() {
+        let dfa = Dfa::new(2, 0, vec![vec![], vec![]], 1, vec![1]).unwrap();
+        let text = dfa.to_text();
+        assert_eq!(
+            text,
+            "states 2\nalphabets 0\ninitial 1\naccept 1\ntransitions\n-\n-\n"
+        );
+        let parsed = Dfa::from_text(&text).unwrap();
+        assert_eq!(parsed.transition_function, dfa.transition_function);
+        assert!(parsed.is_language(vec![]));
+    }
+
+    #[test]
+    fn test_text_duplicate_keyword() {
+        let text = "states 2\nalphabets 1\nstates 3\ninitial 0\ntransitions\n0\n0\n";
+        assert_eq!(
+            Dfa::from_text(text).unwrap_err(),
+            ParseError::Syntax {
+                line: 3,
+                column: 1,
+                message: "duplicate 'states'".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_text_comments_and_order() {
+        let text = "# parity of 1\n\naccept 0\ninitial 0  # start here\nalphabets 2\nstates 2\ntransitions\n0 1\n1 0\n";
+        let parsed = Dfa::from_text(text).unwrap();
+        assert!(parsed.is_language(vec![1, 0, 1]));
+        assert!(!parsed.is_language(vec![1]));
+    }
+
+    #[test]
+    fn test_text_syntax_error() {
+        let text = "states 2\nalphabets 2\ninitial 0\ntransitions\n0 1\n1 x\n";
+        assert_eq!(
+            Dfa::from_text(text).unwrap_err(),
+            ParseError::Syntax {
+                line: 6,
+                column: 3,
+                message: "expected a number, found 'x'".to_string()
+            }
+        );
+
+        let text = "states 2\n  alphabet 2\n";
+        assert_eq!(
+            Dfa::from_text(text).unwrap_err(),
+            ParseError::Syntax {
+                line: 2,
+                column: 3,
+                message: "unknown keyword 'alphabet'".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_text_invalid_dfa() {
+        let text = "states 2\nalphabets 2\ninitial 0\ntransitions\n0 1\n";
+        assert_eq!(
+            Dfa::from_text(text).unwrap_err(),
+            ParseError::Invalid(DfaError::WrongRowCount {
+                expected: 2,
+                found: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let json = dfa().to_json();
+        let parsed = Dfa::from_json(&json).unwrap();
+        assert_eq!(parsed.transition_function, dfa().transition_function);
+        assert_eq!(parsed.equivalent(&dfa()), Ok(()));
+    }
+
+    #[test]
+    fn test_json_key_order() {
+        let json = r#"{"transition_function": [[0, 1], [1, 0]], "accept_states": [],
+            "initial_state": 1, "alphabets": 2, "states": 2}"#;
+        let parsed = Dfa::from_json(json).unwrap();
+        assert_eq!(parsed.initial_state, 1);
+        assert!(parsed.accept_states.is_empty());
+    }
+
+    #[test]
+    fn test_json_syntax_error() {
+        let json = "{\n  \"states\": 2,\n  \"alphabets\": two\n}";
+        assert_eq!(
+            Dfa::from_json(json).unwrap_err(),
+            ParseError::Syntax {
+                line: 3,
+                column: 16,
+                message: "expected a number, found 't'".to_string()
+            }
+        );
+
+        let json = "{\"states\": 1, \"alphabets\": 1, \"initial_state\": 0, \"accept_states\": []}";
+        assert_eq!(
+            Dfa::from_json(json).unwrap_err(),
+            ParseError::Syntax {
+                line: 1,
+                column: 1,
+                message: "missing key \"transition_function\"".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_json_invalid_dfa() {
+        let json = r#"{"states": 1, "alphabets": 1, "initial_state": 0,
+            "accept_states": [], "transition_function": [[1]]}"#;
+        assert_eq!(
+            Dfa::from_json(json).unwrap_err(),
+            ParseError::Invalid(DfaError::TargetOutOfRange {
+                state: 0,
+                alphabet: 0,
+                target: 1
+            })
+        );
+    }
+}
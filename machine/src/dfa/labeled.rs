@@ -0,0 +1,308 @@
+use super::{Dfa, DfaError};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Dfa whose states are labelled with S and alphabets with A.
+/// Labels are compiled down to the u64 ids of the underlying Dfa
+/// in order of first appearance in the builder.
+#[derive(Debug, Clone)]
+pub struct LabeledDfa<S, A> {
+    dfa: Dfa,
+    states: Vec<S>,
+    alphabets: Vec<A>,
+    state_ids: HashMap<S, u64>,
+    alphabet_ids: HashMap<A, u64>,
+}
+
+/// Builder of LabeledDfa from named transitions.
+///
+/// ```
+/// use machine::dfa::LabeledDfa;
+///
+/// let dfa = LabeledDfa::builder()
+///     .initial("even")
+///     .accept("even")
+///     .transition("even", 'a', "odd")
+///     .transition("odd", 'a', "even")
+///     .build()
+///     .unwrap();
+/// assert_eq!(dfa.execute_str("aa"), Ok(true));
+/// ```
+#[derive(Debug, Clone)]
+pub struct LabeledDfaBuilder<S, A> {
+    initial: Option<S>,
+    accepts: Vec<S>,
+    transitions: Vec<(S, A, S)>,
+}
+
+impl<S, A> LabeledDfa<S, A>
+where
+    S: Eq + Hash + Clone,
+    A: Eq + Hash + Clone,
+{
+    pub fn builder() -> LabeledDfaBuilder<S, A> {
+        LabeledDfaBuilder {
+            initial: None,
+            accepts: Vec::new(),
+            transitions: Vec::new(),
+        }
+    }
+
+    /// Underlying Dfa over u64 ids.
+    pub fn dfa(&self) -> &Dfa {
+        &self.dfa
+    }
+
+    pub fn state_id(&self, state: &S) -> Option<u64> {
+        self.state_ids.get(state).copied()
+    }
+
+    pub fn state_label(&self, id: u64) -> Option<&S> {
+        self.states.get(id as usize)
+    }
+
+    pub fn alphabet_id(&self, alphabet: &A) -> Option<u64> {
+        self.alphabet_ids.get(alphabet).copied()
+    }
+
+    pub fn alphabet_label(&self, id: u64) -> Option<&A> {
+        self.alphabets.get(id as usize)
+    }
+
+    /// Translate labelled input into alphabet ids of the underlying Dfa.
+    pub fn encode<I>(&self, input: I) -> Result<Vec<u64>, DfaError>
+    where
+        I: IntoIterator<Item = A>,
+    {
+        input
+            .into_iter()
+            .enumerate()
+            .map(|(position, alphabet)| {
+                self.alphabet_id(&alphabet)
+                    .ok_or(DfaError::UnknownSymbol { position })
+            })
+            .collect()
+    }
+
+    pub fn execute<I>(&self, input: I) -> Result<bool, DfaError>
+    where
+        I: IntoIterator<Item = A>,
+    {
+        self.dfa.execute(self.encode(input)?)
+    }
+
+    pub fn is_language<I>(&self, input: I) -> bool
+    where
+        I: IntoIterator<Item = A>,
+    {
+        self.execute(input).unwrap_or(false)
+    }
+}
+
+impl<S> LabeledDfa<S, char>
+where
+    S: Eq + Hash + Clone,
+{
+    pub fn execute_str(&self, input: &str) -> Result<bool, DfaError> {
+        self.execute(input.chars())
+    }
+}
+
+impl<S> LabeledDfa<S, u8>
+where
+    S: Eq + Hash + Clone,
+{
+    pub fn execute_bytes(&self, input: &[u8]) -> Result<bool, DfaError> {
+        self.execute(input.iter().copied())
+    }
+}
+
+impl<S, A> LabeledDfaBuilder<S, A>
+where
+    S: Eq + Hash + Clone,
+    A: Eq + Hash + Clone,
+{
+    pub fn initial(mut self, state: S) -> Self {
+        self.initial = Some(state);
+        self
+    }
+
+    pub fn accept(mut self, state: S) -> Self {
+        self.accepts.push(state);
+        self
+    }
+
+    /// Add transition from --alphabet--> to.
+    pub fn transition(mut self, from: S, alphabet: A, to: S) -> Self {
+        self.transitions.push((from, alphabet, to));
+        self
+    }
+
+    /// Assign ids to labels and build the dense table.
+    /// Every state needs exactly one transition for every alphabet.
+    pub fn build(self) -> Result<LabeledDfa<S, A>, DfaError> {
+        let initial = self.initial.ok_or(DfaError::MissingInitialState)?;
+
+        let mut states = Vec::new();
+        let mut state_ids = HashMap::new();
+        let mut alphabets = Vec::new();
+        let mut alphabet_ids = HashMap::new();
+        let mut intern = |label: &S| {
+            *state_ids.entry(label.clone()).or_insert_with(|| {
+                states.push(label.clone());
+                states.len() as u64 - 1
+            })
+        };
+
+        let initial_state = intern(&initial);
+        let mut edges = Vec::with_capacity(self.transitions.len());
+        for (from, alphabet, to) in &self.transitions {
+            let alphabet = *alphabet_ids.entry(alphabet.clone()).or_insert_with(|| {
+                alphabets.push(alphabet.clone());
+                alphabets.len() as u64 - 1
+            });
+            edges.push((intern(from), alphabet, intern(to)));
+        }
+        let accept_states: Vec<u64> = self.accepts.iter().map(&mut intern).collect();
+
+        let mut table = vec![vec![None; alphabets.len()]; states.len()];
+        for (from, alphabet, to) in edges {
+            let cell = &mut table[from as usize][alphabet as usize];
+            if cell.is_some() {
+                return Err(DfaError::DuplicateTransition {
+                    state: from,
+                    alphabet,
+                });
+            }
+            *cell = Some(to);
+        }
+        let transition_function = table
+            .into_iter()
+            .enumerate()
+            .map(|(state, row)| {
+                row.into_iter()
+                    .enumerate()
+                    .map(|(alphabet, next)| {
+                        next.ok_or(DfaError::MissingTransition {
+                            state: state as u64,
+                            alphabet: alphabet as u64,
+                        })
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+
+        let dfa = Dfa::new(
+            states.len() as u64,
+            alphabets.len() as u64,
+            transition_function,
+            initial_state,
+            accept_states,
+        )?;
+        Ok(LabeledDfa {
+            dfa,
+            states,
+            alphabets,
+            state_ids,
+            alphabet_ids,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // accepts binary strings with even number of 1.
+    fn even_ones() -> LabeledDfa<&'static str, char> {
+        LabeledDfa::builder()
+            .initial("even")
+            .accept("even")
+            .transition("even", '0', "even")
+            .transition("even", '1', "odd")
+            .transition("odd", '0', "odd")
+            .transition("odd", '1', "even")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_execute_str() {
+        let dfa = even_ones();
+        assert_eq!(dfa.execute_str("0110"), Ok(true));
+        assert_eq!(dfa.execute_str("010"), Ok(false));
+        assert_eq!(
+            dfa.execute_str("01a"),
+            Err(DfaError::UnknownSymbol { position: 2 })
+        );
+    }
+
+    #[test]
+    fn test_execute_iterator() {
+        let dfa = even_ones();
+        assert!(dfa.is_language(vec!['1', '1']));
+        assert!(!dfa.is_language("1".chars().cycle().take(3)));
+    }
+
+    #[test]
+    fn test_execute_bytes() {
+        let dfa = LabeledDfa::builder()
+            .initial(0)
+            .accept(1)
+            .transition(0, b'a', 1)
+            .transition(0, b'b', 0)
+            .transition(1, b'a', 1)
+            .transition(1, b'b', 0)
+            .build()
+            .unwrap();
+        assert_eq!(dfa.execute_bytes(b"aba"), Ok(true));
+        assert_eq!(dfa.execute_bytes(b"ab"), Ok(false));
+    }
+
+    #[test]
+    fn test_labels() {
+        let dfa = even_ones();
+        assert_eq!(dfa.state_id(&"even"), Some(0));
+        assert_eq!(dfa.state_label(1), Some(&"odd"));
+        assert_eq!(dfa.alphabet_id(&'1'), Some(1));
+        assert_eq!(dfa.encode("10".chars()), Ok(vec![1, 0]));
+    }
+
+    #[test]
+    fn test_build_missing_transition() {
+        let res = LabeledDfa::builder()
+            .initial("q0")
+            .transition("q0", 'a', "q1")
+            .build();
+        assert_eq!(
+            res.unwrap_err(),
+            DfaError::MissingTransition {
+                state: 1,
+                alphabet: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_build_duplicate_transition() {
+        let res = LabeledDfa::builder()
+            .initial("q0")
+            .transition("q0", 'a', "q0")
+            .transition("q0", 'a', "q1")
+            .transition("q1", 'a', "q1")
+            .build();
+        assert_eq!(
+            res.unwrap_err(),
+            DfaError::DuplicateTransition {
+                state: 0,
+                alphabet: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_build_missing_initial_state() {
+        let res: Result<LabeledDfa<&str, char>, _> = LabeledDfa::builder().build();
+        assert_eq!(res.unwrap_err(), DfaError::MissingInitialState);
+    }
+}
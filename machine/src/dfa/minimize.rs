@@ -0,0 +1,226 @@
+use super::Dfa;
+use std::collections::{HashSet, VecDeque};
+use std::iter::FromIterator;
+
+impl Dfa {
+    /// Minimize this automaton with Hopcroft's partition refinement.
+    /// Returns the minimal Dfa accepting the same language and a mapping
+    /// from old state ids to new ones. mapping[old] is None if the old state
+    /// is unreachable from the initial state, because such a state is dropped.
+    /// States of the minimal Dfa are numbered in breadth first order from
+    /// the initial state, so the new initial state is always 0.
+    pub fn minimize(&self) -> (Dfa, Vec<Option<u64>>) {
+        let n = self.states as usize;
+        let k = self.alphabets as usize;
+        let reachable = self.reachable_states();
+
+        // inverse[a][q] holds every reachable state p with delta(p, a) = q.
+        let mut inverse = vec![vec![Vec::new(); n]; k];
+        for (p, row) in self.transition_function.iter().enumerate() {
+            if !reachable[p] {
+                continue;
+            }
+            for (a, &q) in row.iter().enumerate() {
+                inverse[a][q as usize].push(p);
+            }
+        }
+
+        // initial partition is {accepting, non accepting} over reachable states.
+        let (accepting, rejecting): (Vec<usize>, Vec<usize>) = (0..n)
+            .filter(|&q| reachable[q])
+            .partition(|q| self.accept_states.contains(&(*q as u64)));
+        let mut blocks: Vec<Vec<usize>> = vec![accepting, rejecting]
+            .into_iter()
+            .filter(|b| !b.is_empty())
+            .collect();
+        let mut block_of = vec![usize::MAX; n];
+        for (i, block) in blocks.iter().enumerate() {
+            for &q in block {
+                block_of[q] = i;
+            }
+        }
+
+        // it is enough to start with the smaller of the two blocks.
+        let mut waiting: HashSet<usize> = HashSet::new();
+        if blocks.len() == 2 {
+            let smaller = if blocks[0].len() <= blocks[1].len() {
+                0
+            } else {
+                1
+            };
+            waiting.insert(smaller);
+        }
+
+        while let Some(&splitter) = waiting.iter().next() {
+            waiting.remove(&splitter);
+            let splitter_states = blocks[splitter].clone();
+            for inverse_a in &inverse {
+                // states which move into the splitter on this alphabet.
+                let mut predecessors = vec![false; n];
+                let mut touched = Vec::new();
+                for &q in &splitter_states {
+                    for &p in &inverse_a[q] {
+                        if !predecessors[p] {
+                            predecessors[p] = true;
+                            if !touched.contains(&block_of[p]) {
+                                touched.push(block_of[p]);
+                            }
+                        }
+                    }
+                }
+
+                for y in touched {
+                    let (inside, outside): (Vec<usize>, Vec<usize>) =
+                        blocks[y].iter().partition(|&&q| predecessors[q]);
+                    if outside.is_empty() {
+                        continue;
+                    }
+                    let z = blocks.len();
+                    for &q in &outside {
+                        block_of[q] = z;
+                    }
+                    let smaller = if inside.len() <= outside.len() { y } else { z };
+                    blocks[y] = inside;
+                    blocks.push(outside);
+                    if waiting.contains(&y) {
+                        waiting.insert(z);
+                    } else {
+                        waiting.insert(smaller);
+                    }
+                }
+            }
+        }
+
+        // renumber blocks in breadth first order from the initial state.
+        let mut new_id = vec![None; blocks.len()];
+        let mut order = Vec::new();
+        let mut queue = VecDeque::new();
+        let initial_block = block_of[self.initial_state as usize];
+        new_id[initial_block] = Some(0);
+        order.push(initial_block);
+        queue.push_back(initial_block);
+        while let Some(b) = queue.pop_front() {
+            let representative = blocks[b][0];
+            for &next in &self.transition_function[representative] {
+                let next_block = block_of[next as usize];
+                if new_id[next_block].is_none() {
+                    new_id[next_block] = Some(order.len() as u64);
+                    order.push(next_block);
+                    queue.push_back(next_block);
+                }
+            }
+        }
+
+        let transition_function = order
+            .iter()
+            .map(|&b| {
+                self.transition_function[blocks[b][0]]
+                    .iter()
+                    .map(|&next| new_id[block_of[next as usize]].unwrap())
+                    .collect()
+            })
+            .collect();
+        let accept_states = order
+            .iter()
+            .enumerate()
+            .filter(|(_, &b)| self.accept_states.contains(&(blocks[b][0] as u64)))
+            .map(|(i, _)| i as u64);
+        let mapping = (0..n)
+            .map(|q| {
+                if reachable[q] {
+                    new_id[block_of[q]]
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        let minimal = Dfa {
+            states: order.len() as u64,
+            alphabets: self.alphabets,
+            transition_function,
+            initial_state: 0,
+            accept_states: HashSet::from_iter(accept_states),
+        };
+        (minimal, mapping)
+    }
+
+    /// reachable[q] is true if state q can be reached from the initial state.
+    pub(crate) fn reachable_states(&self) -> Vec<bool> {
+        reachable_states(&self.transition_function, self.initial_state)
+    }
+}
+
+/// reachable[q] is true if state q can be reached from initial_state,
+/// following the targets of transition_function which are not None.
+pub(crate) fn reachable_states<T>(transition_function: &[Vec<T>], initial_state: u64) -> Vec<bool>
+where
+    T: Copy + Into<Option<u64>>,
+{
+    let mut reachable = vec![false; transition_function.len()];
+    let mut stack = vec![initial_state as usize];
+    reachable[initial_state as usize] = true;
+    while let Some(q) = stack.pop() {
+        for next in transition_function[q]
+            .iter()
+            .filter_map(|&next| next.into())
+        {
+            if !reachable[next as usize] {
+                reachable[next as usize] = true;
+                stack.push(next as usize);
+            }
+        }
+    }
+    reachable
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minimize_merges_equivalent_states() {
+        // states 1 and 2 are equivalent, both accept and loop into each other.
+        let transition_function = vec![vec![1, 2], vec![2, 1], vec![1, 2]];
+        let dfa = Dfa::new(3, 2, transition_function, 0, vec![1, 2]).unwrap();
+        let (minimal, mapping) = dfa.minimize();
+
+        assert_eq!(minimal.states, 2);
+        assert_eq!(mapping, vec![Some(0), Some(1), Some(1)]);
+        assert_eq!(dfa.equivalent(&minimal), Ok(()));
+    }
+
+    #[test]
+    fn test_minimize_drops_unreachable_states() {
+        let transition_function = vec![vec![1, 0], vec![0, 1], vec![2, 2]];
+        let dfa = Dfa::new(3, 2, transition_function, 0, vec![0, 2]).unwrap();
+        let (minimal, mapping) = dfa.minimize();
+
+        assert_eq!(minimal.states, 2);
+        assert_eq!(mapping, vec![Some(0), Some(1), None]);
+    }
+
+    #[test]
+    fn test_minimize_ends_with_abb() {
+        // textbook example: 5 states recognising words over {a, b} ending with "abb"
+        // where states 0 and 2 are equivalent.
+        let transition_function = vec![vec![1, 2], vec![1, 3], vec![1, 2], vec![1, 4], vec![1, 2]];
+        let dfa = Dfa::new(5, 2, transition_function, 0, vec![4]).unwrap();
+        let (minimal, mapping) = dfa.minimize();
+
+        assert_eq!(minimal.states, 4);
+        assert_eq!(mapping[0], mapping[2]);
+        assert_eq!(dfa.equivalent(&minimal), Ok(()));
+    }
+
+    #[test]
+    fn test_minimize_is_idempotent() {
+        let transition_function = vec![vec![1, 0], vec![0, 1]];
+        let dfa = Dfa::new(2, 2, transition_function, 0, vec![0]).unwrap();
+        let (minimal, _) = dfa.minimize();
+        let (again, mapping) = minimal.minimize();
+
+        assert_eq!(again.transition_function, minimal.transition_function);
+        assert_eq!(mapping, vec![Some(0), Some(1)]);
+    }
+}
@@ -0,0 +1,67 @@
+/// Observer of transitions taken while executing a Dfa.
+/// Any FnMut(from, alphabet, to) closure can be used as an observer.
+pub trait Observer {
+    fn on_transition(&mut self, from: u64, alphabet: u64, to: u64);
+}
+
+/// Observer which does nothing, used by Dfa::execute.
+/// Calls to it are inlined away so execution has no extra cost.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoObserver;
+
+/// Observer collecting every transition as (from, alphabet, to).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Trace {
+    pub steps: Vec<(u64, u64, u64)>,
+}
+
+impl Observer for NoObserver {
+    #[inline(always)]
+    fn on_transition(&mut self, _from: u64, _alphabet: u64, _to: u64) {}
+}
+
+impl Observer for Trace {
+    fn on_transition(&mut self, from: u64, alphabet: u64, to: u64) {
+        self.steps.push((from, alphabet, to));
+    }
+}
+
+impl<F> Observer for F
+where
+    F: FnMut(u64, u64, u64),
+{
+    fn on_transition(&mut self, from: u64, alphabet: u64, to: u64) {
+        self(from, alphabet, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dfa::Dfa;
+
+    #[test]
+    fn test_trace() {
+        let dfa = Dfa::new(2, 2, vec![vec![1, 0], vec![0, 1]], 0, vec![0]).unwrap();
+        let mut trace = Trace::default();
+        assert_eq!(dfa.execute_with(vec![0, 1, 0], &mut trace), Ok(true));
+        assert_eq!(trace.steps, vec![(0, 0, 1), (1, 1, 1), (1, 0, 0)]);
+    }
+
+    #[test]
+    fn test_callback() {
+        let dfa = Dfa::new(2, 2, vec![vec![1, 0], vec![0, 1]], 0, vec![0]).unwrap();
+        let mut visited = Vec::new();
+        let res = dfa.execute_with(vec![0, 0], &mut |_, _, to| visited.push(to));
+        assert_eq!(res, Ok(true));
+        assert_eq!(visited, vec![1, 0]);
+    }
+
+    #[test]
+    fn test_not_called_on_invalid_input() {
+        let dfa = Dfa::new(2, 2, vec![vec![1, 0], vec![0, 1]], 0, vec![0]).unwrap();
+        let mut trace = Trace::default();
+        assert!(dfa.execute_with(vec![0, 2], &mut trace).is_err());
+        assert!(trace.steps.is_empty());
+    }
+}
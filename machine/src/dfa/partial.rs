@@ -0,0 +1,277 @@
+use super::minimize::reachable_states;
+use super::runner::live_states;
+use super::{validate_table, Dfa, DfaError};
+use std::collections::HashSet;
+use std::iter::FromIterator;
+
+/// Dfa whose transition function may be undefined for some state and alphabet.
+/// A missing transition, expressed as None, goes to an implicit dead state
+/// which never accepts, so sparse automata do not need a hand-made sink state.
+#[derive(Debug, Clone)]
+pub struct PartialDfa {
+    states: u64,
+    alphabets: u64,
+    transition_function: Vec<Vec<Option<u64>>>,
+    initial_state: u64,
+    accept_states: HashSet<u64>,
+}
+
+impl PartialDfa {
+    /// Build a PartialDfa with the same validation as Dfa::new,
+    /// except that transitions may be None.
+    pub fn new(
+        states: u64,
+        alphabets: u64,
+        transition_function: Vec<Vec<Option<u64>>>,
+        initial_state: u64,
+        accept_states: Vec<u64>,
+    ) -> Result<Self, DfaError> {
+        validate_table(states, alphabets, &transition_function, initial_state)?;
+        if let Some(&state) = accept_states.iter().find(|&&q| q >= states) {
+            return Err(DfaError::AcceptStateOutOfRange(state));
+        }
+
+        Ok(PartialDfa {
+            states,
+            alphabets,
+            transition_function,
+            initial_state,
+            accept_states: HashSet::from_iter(accept_states),
+        })
+    }
+
+    pub fn states(&self) -> u64 {
+        self.states
+    }
+
+    pub fn alphabets(&self) -> u64 {
+        self.alphabets
+    }
+
+    pub fn initial_state(&self) -> u64 {
+        self.initial_state
+    }
+
+    pub fn is_accept_state(&self, state: u64) -> bool {
+        self.accept_states.contains(&state)
+    }
+
+    /// State reached from state on alphabet, None if the transition is missing
+    /// or either is out of range.
+    pub fn next_state(&self, state: u64, alphabet: u64) -> Option<u64> {
+        self.transition_function
+            .get(state as usize)?
+            .get(alphabet as usize)
+            .copied()?
+    }
+
+    /// Whether every transition is defined.
+    pub fn is_complete(&self) -> bool {
+        self.transition_function
+            .iter()
+            .all(|row| row.iter().all(Option::is_some))
+    }
+
+    pub fn execute(&self, input: Vec<u64>) -> Result<bool, DfaError> {
+        if let Some(position) = input.iter().position(|a| a >= &self.alphabets) {
+            return Err(DfaError::InvalidInput {
+                position,
+                alphabet: input[position],
+            });
+        }
+
+        let mut state = self.initial_state;
+        for alphabet in input {
+            match self.transition_function[state as usize][alphabet as usize] {
+                Some(next) => state = next,
+                None => return Ok(false),
+            }
+        }
+        Ok(self.accept_states.contains(&state))
+    }
+
+    pub fn is_language(&self, input: Vec<u64>) -> bool {
+        self.execute(input).unwrap_or(false)
+    }
+
+    /// Dfa accepting the same language where the dead state is made explicit.
+    /// The dead state is added as the last state only if some transition is missing.
+    pub fn complete(&self) -> Dfa {
+        let dead = self.states;
+        let states = if self.is_complete() {
+            self.states
+        } else {
+            self.states + 1
+        };
+        let mut transition_function: Vec<Vec<u64>> = self
+            .transition_function
+            .iter()
+            .map(|row| row.iter().map(|next| next.unwrap_or(dead)).collect())
+            .collect();
+        if states > self.states {
+            transition_function.push(vec![dead; self.alphabets as usize]);
+        }
+
+        Dfa {
+            states,
+            alphabets: self.alphabets,
+            transition_function,
+            initial_state: self.initial_state,
+            accept_states: self.accept_states.clone(),
+        }
+    }
+
+    /// PartialDfa accepting the same language without states that are unreachable
+    /// from the initial state or from which no accept state can be reached.
+    /// Transitions into removed states become missing. The initial state is always kept,
+    /// so the result has a single state if the language is empty.
+    pub fn trim(&self) -> PartialDfa {
+        let n = self.states as usize;
+
+        let reachable = reachable_states(&self.transition_function, self.initial_state);
+        let live = live_states(&self.transition_function, &self.accept_states);
+
+        let initial = self.initial_state as usize;
+        let mut new_id = vec![None; n];
+        let mut kept = Vec::new();
+        for q in 0..n {
+            if (reachable[q] && live[q]) || q == initial {
+                new_id[q] = Some(kept.len() as u64);
+                kept.push(q);
+            }
+        }
+        let transition_function = kept
+            .iter()
+            .map(|&q| {
+                self.transition_function[q]
+                    .iter()
+                    .map(|next| next.and_then(|next| new_id[next as usize]))
+                    .map(|next| next.filter(|&next| live[kept[next as usize]]))
+                    .collect()
+            })
+            .collect();
+        let accept_states = kept
+            .iter()
+            .filter(|&&q| self.accept_states.contains(&(q as u64)))
+            .map(|&q| new_id[q].unwrap())
+            .collect();
+
+        PartialDfa {
+            states: kept.len() as u64,
+            alphabets: self.alphabets,
+            transition_function,
+            initial_state: new_id[initial].unwrap(),
+            accept_states,
+        }
+    }
+}
+
+impl From<Dfa> for PartialDfa {
+    fn from(dfa: Dfa) -> Self {
+        PartialDfa {
+            states: dfa.states,
+            alphabets: dfa.alphabets,
+            transition_function: dfa
+                .transition_function
+                .into_iter()
+                .map(|row| row.into_iter().map(Some).collect())
+                .collect(),
+            initial_state: dfa.initial_state,
+            accept_states: dfa.accept_states,
+        }
+    }
+}
+
+impl Dfa {
+    /// Remove unreachable states and states from which no accept state can be reached,
+    /// for example a sink state. See PartialDfa::trim.
+    pub fn trim(&self) -> PartialDfa {
+        PartialDfa::from(self.clone()).trim()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // accepts exactly 0 1 and 0 0 1.
+    fn sparse() -> PartialDfa {
+        PartialDfa::new(
+            4,
+            2,
+            vec![
+                vec![Some(1), None],
+                vec![Some(2), Some(3)],
+                vec![None, Some(3)],
+                vec![None, None],
+            ],
+            0,
+            vec![3],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_execute_partial() {
+        let dfa = sparse();
+        assert_eq!(dfa.execute(vec![0, 1]), Ok(true));
+        assert_eq!(dfa.execute(vec![0, 0, 1]), Ok(true));
+        assert_eq!(dfa.execute(vec![1, 1]), Ok(false));
+        assert_eq!(dfa.execute(vec![0, 1, 1]), Ok(false));
+        assert!(dfa.execute(vec![0, 2]).is_err());
+    }
+
+    #[test]
+    fn test_new_target_out_of_range() {
+        let res = PartialDfa::new(1, 1, vec![vec![Some(1)]], 0, vec![]);
+        assert_eq!(
+            res.unwrap_err(),
+            DfaError::TargetOutOfRange {
+                state: 0,
+                alphabet: 0,
+                target: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_complete() {
+        let dfa = sparse().complete();
+        assert_eq!(dfa.states(), 5);
+        assert_eq!(dfa.next_state(0, 1), Some(4));
+        assert_eq!(dfa.next_state(4, 0), Some(4));
+        assert!(dfa.is_language(vec![0, 0, 1]));
+        assert!(!dfa.is_language(vec![0, 0, 1, 0]));
+
+        // no dead state is added to a complete automaton.
+        let dfa = Dfa::new(1, 1, vec![vec![0]], 0, vec![0]).unwrap();
+        assert_eq!(PartialDfa::from(dfa).complete().states(), 1);
+    }
+
+    #[test]
+    fn test_trim() {
+        // state 2 is unreachable and state 3 is a sink.
+        let dfa = Dfa::new(
+            4,
+            2,
+            vec![vec![1, 3], vec![0, 3], vec![1, 1], vec![3, 3]],
+            0,
+            vec![1],
+        )
+        .unwrap();
+        let trimmed = dfa.trim();
+        assert_eq!(trimmed.states(), 2);
+        assert_eq!(trimmed.next_state(0, 0), Some(1));
+        assert_eq!(trimmed.next_state(0, 1), None);
+        assert_eq!(trimmed.complete().equivalent(&dfa), Ok(()));
+    }
+
+    #[test]
+    fn test_trim_empty_language() {
+        let dfa = Dfa::new(2, 1, vec![vec![0], vec![1]], 0, vec![1]).unwrap();
+        let trimmed = dfa.trim();
+        assert_eq!(trimmed.states(), 1);
+        assert_eq!(trimmed.next_state(0, 0), None);
+        assert!(!trimmed.is_language(vec![]));
+    }
+}
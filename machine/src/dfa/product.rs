@@ -0,0 +1,162 @@
+use super::{Dfa, DfaError};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+impl Dfa {
+    /// Dfa accepting exactly the inputs this Dfa rejects.
+    pub fn complement(&self) -> Dfa {
+        let accept_states = (0..self.states)
+            .filter(|q| !self.accept_states.contains(q))
+            .collect();
+        Dfa {
+            states: self.states,
+            alphabets: self.alphabets,
+            transition_function: self.transition_function.clone(),
+            initial_state: self.initial_state,
+            accept_states,
+        }
+    }
+
+    /// Dfa accepting inputs accepted by self or other.
+    pub fn union(&self, other: &Dfa) -> Result<Dfa, DfaError> {
+        self.product(other, |a, b| a || b)
+    }
+
+    /// Dfa accepting inputs accepted by both self and other.
+    pub fn intersection(&self, other: &Dfa) -> Result<Dfa, DfaError> {
+        self.product(other, |a, b| a && b)
+    }
+
+    /// Dfa accepting inputs accepted by self but not by other.
+    pub fn difference(&self, other: &Dfa) -> Result<Dfa, DfaError> {
+        self.product(other, |a, b| a && !b)
+    }
+
+    /// Dfa accepting inputs accepted by exactly one of self and other.
+    pub fn symmetric_difference(&self, other: &Dfa) -> Result<Dfa, DfaError> {
+        self.product(other, |a, b| a != b)
+    }
+
+    /// Product construction over the pairs of states reachable from
+    /// (self.initial_state, other.initial_state).
+    /// A pair is accepting if accept(self accepts, other accepts) is true.
+    /// Both Dfas must share the same alphabets.
+    fn product<F>(&self, other: &Dfa, accept: F) -> Result<Dfa, DfaError>
+    where
+        F: Fn(bool, bool) -> bool,
+    {
+        if self.alphabets != other.alphabets {
+            return Err(DfaError::AlphabetMismatch {
+                left: self.alphabets,
+                right: other.alphabets,
+            });
+        }
+
+        let initial = (self.initial_state, other.initial_state);
+        let mut ids: HashMap<(u64, u64), u64> = HashMap::new();
+        let mut pairs = vec![initial];
+        let mut queue = VecDeque::new();
+        ids.insert(initial, 0);
+        queue.push_back(initial);
+
+        let mut transition_function = Vec::new();
+        while let Some((p, q)) = queue.pop_front() {
+            let row = (0..self.alphabets as usize)
+                .map(|a| {
+                    let next = (
+                        self.transition_function[p as usize][a],
+                        other.transition_function[q as usize][a],
+                    );
+                    *ids.entry(next).or_insert_with(|| {
+                        pairs.push(next);
+                        queue.push_back(next);
+                        pairs.len() as u64 - 1
+                    })
+                })
+                .collect();
+            transition_function.push(row);
+        }
+
+        let accept_states: HashSet<u64> = pairs
+            .iter()
+            .enumerate()
+            .filter(|(_, (p, q))| {
+                accept(
+                    self.accept_states.contains(p),
+                    other.accept_states.contains(q),
+                )
+            })
+            .map(|(i, _)| i as u64)
+            .collect();
+
+        Ok(Dfa {
+            states: pairs.len() as u64,
+            alphabets: self.alphabets,
+            transition_function,
+            initial_state: 0,
+            accept_states,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // accepts inputs with even number of 1.
+    fn even_ones() -> Dfa {
+        Dfa::new(2, 2, vec![vec![0, 1], vec![1, 0]], 0, vec![0]).unwrap()
+    }
+
+    // accepts inputs with even number of 0.
+    fn even_zeros() -> Dfa {
+        Dfa::new(2, 2, vec![vec![1, 0], vec![0, 1]], 0, vec![0]).unwrap()
+    }
+
+    #[test]
+    fn test_complement() {
+        let dfa = even_ones().complement();
+        assert!(dfa.is_language(vec![1, 0]));
+        assert!(!dfa.is_language(vec![1, 1]));
+    }
+
+    #[test]
+    fn test_union() {
+        let dfa = even_ones().union(&even_zeros()).unwrap();
+        assert!(dfa.is_language(vec![1, 1, 0]));
+        assert!(dfa.is_language(vec![0, 0, 1]));
+        assert!(!dfa.is_language(vec![0, 1]));
+    }
+
+    #[test]
+    fn test_intersection() {
+        let dfa = even_ones().intersection(&even_zeros()).unwrap();
+        assert!(dfa.is_language(vec![1, 0, 1, 0]));
+        assert!(!dfa.is_language(vec![1, 1, 0]));
+    }
+
+    #[test]
+    fn test_difference() {
+        let dfa = even_ones().difference(&even_zeros()).unwrap();
+        assert!(dfa.is_language(vec![1, 1, 0]));
+        assert!(!dfa.is_language(vec![1, 1, 0, 0]));
+        assert!(!dfa.is_language(vec![1, 0, 0]));
+    }
+
+    #[test]
+    fn test_symmetric_difference() {
+        let dfa = even_ones().symmetric_difference(&even_zeros()).unwrap();
+        assert!(dfa.is_language(vec![1, 1, 0]));
+        assert!(dfa.is_language(vec![0, 0, 1]));
+        assert!(!dfa.is_language(vec![1, 0]));
+        assert!(!dfa.is_language(vec![1, 1, 0, 0]));
+    }
+
+    #[test]
+    fn test_product_alphabet_mismatch() {
+        let other = Dfa::new(1, 3, vec![vec![0, 0, 0]], 0, vec![0]).unwrap();
+        assert_eq!(
+            even_ones().union(&other).unwrap_err(),
+            DfaError::AlphabetMismatch { left: 2, right: 3 }
+        );
+    }
+}
@@ -0,0 +1,229 @@
+use super::{Dfa, DfaError};
+use std::collections::HashSet;
+
+/// Incremental execution of a Dfa.
+/// Input is fed one alphabet or one chunk at a time,
+/// and the current state can be inspected at any point.
+#[derive(Debug, Clone)]
+pub struct DfaRunner<'a> {
+    dfa: &'a Dfa,
+    live: Vec<bool>,
+    state: u64,
+    position: usize,
+}
+
+/// Saved state of a DfaRunner which can be restored later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunnerSnapshot {
+    state: u64,
+    position: usize,
+}
+
+impl Dfa {
+    /// Start incremental execution from the initial state.
+    pub fn runner(&self) -> DfaRunner<'_> {
+        DfaRunner {
+            dfa: self,
+            live: self.live_states(),
+            state: self.initial_state,
+            position: 0,
+        }
+    }
+
+    /// live[q] is true if some accept state can be reached from state q.
+    pub(crate) fn live_states(&self) -> Vec<bool> {
+        live_states(&self.transition_function, &self.accept_states)
+    }
+}
+
+/// live[q] is true if some of accept_states can be reached from state q,
+/// following the targets of transition_function which are not None.
+pub(crate) fn live_states<T>(
+    transition_function: &[Vec<T>],
+    accept_states: &HashSet<u64>,
+) -> Vec<bool>
+where
+    T: Copy + Into<Option<u64>>,
+{
+    let n = transition_function.len();
+    let mut inverse = vec![Vec::new(); n];
+    for (p, row) in transition_function.iter().enumerate() {
+        for q in row.iter().filter_map(|&next| next.into()) {
+            inverse[q as usize].push(p);
+        }
+    }
+
+    let mut live = vec![false; n];
+    let mut stack: Vec<usize> = accept_states.iter().map(|&q| q as usize).collect();
+    for &q in &stack {
+        live[q] = true;
+    }
+    while let Some(q) = stack.pop() {
+        for &p in &inverse[q] {
+            if !live[p] {
+                live[p] = true;
+                stack.push(p);
+            }
+        }
+    }
+    live
+}
+
+impl<'a> DfaRunner<'a> {
+    /// Feed one alphabet and return the new current state.
+    pub fn feed(&mut self, alphabet: u64) -> Result<u64, DfaError> {
+        if alphabet >= self.dfa.alphabets {
+            return Err(DfaError::InvalidInput {
+                position: self.position,
+                alphabet,
+            });
+        }
+        self.state = self.dfa.transition(self.state, alphabet);
+        self.position += 1;
+        Ok(self.state)
+    }
+
+    /// Feed a chunk of alphabets and return the new current state.
+    /// If the chunk contains an invalid alphabet, nothing is consumed
+    /// and the position of the error counts every alphabet fed so far.
+    pub fn feed_all(&mut self, chunk: &[u64]) -> Result<u64, DfaError> {
+        if let Some(i) = chunk.iter().position(|a| a >= &self.dfa.alphabets) {
+            return Err(DfaError::InvalidInput {
+                position: self.position + i,
+                alphabet: chunk[i],
+            });
+        }
+        for &alphabet in chunk {
+            self.state = self.dfa.transition(self.state, alphabet);
+        }
+        self.position += chunk.len();
+        Ok(self.state)
+    }
+
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    /// Number of alphabets consumed since start or last reset.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Whether the input fed so far is accepted.
+    pub fn is_accepting(&self) -> bool {
+        self.dfa.accept_states.contains(&self.state)
+    }
+
+    /// Whether no continuation of the input fed so far can be accepted.
+    pub fn is_dead(&self) -> bool {
+        !self.live[self.state as usize]
+    }
+
+    /// Go back to the initial state.
+    pub fn reset(&mut self) {
+        self.state = self.dfa.initial_state;
+        self.position = 0;
+    }
+
+    pub fn snapshot(&self) -> RunnerSnapshot {
+        RunnerSnapshot {
+            state: self.state,
+            position: self.position,
+        }
+    }
+
+    /// Go back to a snapshot. It should come from a runner of the same Dfa,
+    /// and one whose state does not exist here is rejected without any change.
+    pub fn restore(&mut self, snapshot: RunnerSnapshot) -> Result<(), DfaError> {
+        if snapshot.state >= self.dfa.states {
+            return Err(DfaError::StateOutOfRange(snapshot.state));
+        }
+        self.state = snapshot.state;
+        self.position = snapshot.position;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // accepts inputs starting with 0 1, state 3 is a sink.
+    fn starts_with_01() -> Dfa {
+        Dfa::new(
+            4,
+            2,
+            vec![vec![1, 3], vec![3, 2], vec![2, 2], vec![3, 3]],
+            0,
+            vec![2],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_feed() {
+        let dfa = starts_with_01();
+        let mut runner = dfa.runner();
+        assert!(!runner.is_accepting());
+        assert_eq!(runner.feed(0), Ok(1));
+        assert_eq!(runner.feed(1), Ok(2));
+        assert!(runner.is_accepting());
+        assert_eq!(runner.feed_all(&[0, 1, 1]), Ok(2));
+        assert!(runner.is_accepting());
+        assert_eq!(runner.position(), 5);
+    }
+
+    #[test]
+    fn test_dead() {
+        let dfa = starts_with_01();
+        let mut runner = dfa.runner();
+        runner.feed(1).unwrap();
+        assert!(runner.is_dead());
+        runner.reset();
+        assert!(!runner.is_dead());
+        assert_eq!(runner.state(), 0);
+        assert_eq!(runner.position(), 0);
+    }
+
+    #[test]
+    fn test_invalid_alphabet() {
+        let dfa = starts_with_01();
+        let mut runner = dfa.runner();
+        runner.feed(0).unwrap();
+        assert_eq!(
+            runner.feed_all(&[1, 2]),
+            Err(DfaError::InvalidInput {
+                position: 2,
+                alphabet: 2
+            })
+        );
+        assert_eq!(runner.state(), 1);
+        assert_eq!(runner.position(), 1);
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        let dfa = starts_with_01();
+        let mut runner = dfa.runner();
+        runner.feed(0).unwrap();
+        let snapshot = runner.snapshot();
+        runner.feed(0).unwrap();
+        assert!(runner.is_dead());
+        assert_eq!(runner.restore(snapshot), Ok(()));
+        assert_eq!(runner.feed(1), Ok(2));
+        assert_eq!(runner.position(), 2);
+
+        // state 3 of a larger Dfa does not exist in a single state one.
+        let small = Dfa::new(1, 2, vec![vec![0, 0]], 0, vec![0]).unwrap();
+        let mut other = small.runner();
+        assert_eq!(other.restore(dfa.runner().snapshot()), Ok(()));
+        let mut sink = dfa.runner();
+        sink.feed(1).unwrap();
+        assert_eq!(
+            other.restore(sink.snapshot()),
+            Err(DfaError::StateOutOfRange(3))
+        );
+        assert_eq!(other.state(), 0);
+        assert!(!other.is_dead());
+    }
+}
@@ -0,0 +1,214 @@
+use super::{Dfa, DfaError};
+
+/// How matches are reported by Dfa::find_all.
+/// A plain Dfa has no priority order between its accepting paths,
+/// so leftmost-first matching is served by LeftmostShortest, the earliest accepting end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchKind {
+    /// Non overlapping matches, each starting as far left as possible
+    /// and being the shortest match from there, that is ending at the first
+    /// position where the Dfa accepts.
+    LeftmostShortest,
+    /// Non overlapping matches, each starting as far left as possible
+    /// and ending at the last position where the Dfa accepts.
+    LeftmostLongest,
+    /// Every span the Dfa accepts, ordered by start and then end.
+    Overlapping,
+}
+
+impl Dfa {
+    /// Every span input[start..end] accepted by this Dfa, as (start, end).
+    /// For non overlapping kinds the search resumes at the end of the previous match,
+    /// or one position after it if the match was empty.
+    ///
+    /// Each start position is scanned until no accept state can be reached anymore.
+    /// When the Dfa stays live without accepting, for example on a prefix of a
+    /// match which never completes, every start scans to the end of input and
+    /// the search takes O(n^2) steps on input of length n.
+    pub fn find_all(
+        &self,
+        input: &[u64],
+        kind: MatchKind,
+    ) -> Result<Vec<(usize, usize)>, DfaError> {
+        self.check_input(input)?;
+
+        let live = self.live_states();
+        let mut matches = Vec::new();
+        let mut start = 0;
+        while start <= input.len() {
+            let end = match kind {
+                MatchKind::Overlapping => {
+                    self.scan(input, start, &live, |end| {
+                        matches.push((start, end));
+                        true
+                    });
+                    start += 1;
+                    continue;
+                }
+                MatchKind::LeftmostShortest => self.shortest_end(input, start, &live),
+                MatchKind::LeftmostLongest => self.longest_end(input, start, &live),
+            };
+            match end {
+                Some(end) => {
+                    matches.push((start, end));
+                    start = if end == start { end + 1 } else { end };
+                }
+                None => start += 1,
+            }
+        }
+        Ok(matches)
+    }
+
+    /// First match found by find_all, if any. Overlapping is the same as LeftmostShortest here.
+    /// Like find_all, it can take O(n^2) steps when the Dfa stays live without accepting.
+    pub fn find(&self, input: &[u64], kind: MatchKind) -> Result<Option<(usize, usize)>, DfaError> {
+        self.check_input(input)?;
+
+        let live = self.live_states();
+        for start in 0..=input.len() {
+            let end = if kind == MatchKind::LeftmostLongest {
+                self.longest_end(input, start, &live)
+            } else {
+                self.shortest_end(input, start, &live)
+            };
+            if let Some(end) = end {
+                return Ok(Some((start, end)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Smallest end such that input[start..end] is accepted.
+    fn shortest_end(&self, input: &[u64], start: usize, live: &[bool]) -> Option<usize> {
+        let mut found = None;
+        self.scan(input, start, live, |end| {
+            found = Some(end);
+            false
+        });
+        found
+    }
+
+    /// Largest end such that input[start..end] is accepted.
+    fn longest_end(&self, input: &[u64], start: usize, live: &[bool]) -> Option<usize> {
+        let mut found = None;
+        self.scan(input, start, live, |end| {
+            found = Some(end);
+            true
+        });
+        found
+    }
+
+    /// Call on_end with every end such that input[start..end] is accepted, in increasing
+    /// order, until it returns false. Scanning also stops as soon as no accept state
+    /// can be reached anymore.
+    fn scan<F>(&self, input: &[u64], start: usize, live: &[bool], mut on_end: F)
+    where
+        F: FnMut(usize) -> bool,
+    {
+        let mut state = self.initial_state;
+        if self.accept_states.contains(&state) && !on_end(start) {
+            return;
+        }
+        for (i, &alphabet) in input[start..].iter().enumerate() {
+            if !live[state as usize] {
+                return;
+            }
+            state = self.transition(state, alphabet);
+            if self.accept_states.contains(&state) && !on_end(start + i + 1) {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // accepts 0 1+ , that is 0 followed by one or more 1. state 3 is a sink.
+    fn zero_ones() -> Dfa {
+        Dfa::new(
+            4,
+            2,
+            vec![vec![1, 3], vec![3, 2], vec![3, 2], vec![3, 3]],
+            0,
+            vec![2],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_leftmost_shortest() {
+        let input = vec![1, 0, 1, 1, 0, 0, 1];
+        assert_eq!(
+            zero_ones().find_all(&input, MatchKind::LeftmostShortest),
+            Ok(vec![(1, 3), (5, 7)])
+        );
+    }
+
+    #[test]
+    fn test_leftmost_longest() {
+        let input = vec![1, 0, 1, 1, 0, 0, 1];
+        assert_eq!(
+            zero_ones().find_all(&input, MatchKind::LeftmostLongest),
+            Ok(vec![(1, 4), (5, 7)])
+        );
+    }
+
+    #[test]
+    fn test_overlapping() {
+        let input = vec![0, 1, 1];
+        assert_eq!(
+            zero_ones().find_all(&input, MatchKind::Overlapping),
+            Ok(vec![(0, 2), (0, 3)])
+        );
+    }
+
+    #[test]
+    fn test_empty_matches() {
+        // accepts inputs with even number of 1, including the empty input.
+        let dfa = Dfa::new(2, 2, vec![vec![0, 1], vec![1, 0]], 0, vec![0]).unwrap();
+        assert_eq!(
+            dfa.find_all(&[1, 1], MatchKind::LeftmostShortest),
+            Ok(vec![(0, 0), (1, 1), (2, 2)])
+        );
+        assert_eq!(
+            dfa.find_all(&[1, 1], MatchKind::LeftmostLongest),
+            Ok(vec![(0, 2), (2, 2)])
+        );
+    }
+
+    #[test]
+    fn test_long_input() {
+        // 0 1+ can not be extended past a 0, so every scan stops quickly.
+        let input: Vec<u64> = (0..200_000).map(|i| (i % 3 != 0) as u64).collect();
+        let dfa = zero_ones();
+        assert_eq!(
+            dfa.find_all(&input, MatchKind::LeftmostLongest)
+                .unwrap()
+                .len(),
+            200_000 / 3 + 1
+        );
+        assert_eq!(
+            dfa.find(&input, MatchKind::LeftmostShortest),
+            Ok(Some((0, 2)))
+        );
+    }
+
+    #[test]
+    fn test_find() {
+        let dfa = zero_ones();
+        assert_eq!(
+            dfa.find(&[1, 0, 1, 1], MatchKind::LeftmostLongest),
+            Ok(Some((1, 4)))
+        );
+        assert_eq!(dfa.find(&[1, 1], MatchKind::Overlapping), Ok(None));
+        assert_eq!(
+            dfa.find(&[0, 2], MatchKind::LeftmostShortest),
+            Err(DfaError::InvalidInput {
+                position: 1,
+                alphabet: 2
+            })
+        );
+    }
+}
@@ -0,0 +1,340 @@
+use super::Dfa;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Groups of states which can not be told apart yet.
+type Groups = Vec<Vec<u64>>;
+
+type Pair = (u64, u64);
+
+/// First step of the shortest input merging a pair of states.
+#[derive(Debug, Clone, Copy)]
+struct MergeStep {
+    alphabet: u64,
+    /// pair reached after alphabet, None if it merges the pair.
+    next: Option<usize>,
+    /// length of the whole merging input.
+    length: usize,
+}
+
+impl Dfa {
+    /// Whether some input leads every state to the same state.
+    pub fn is_synchronizing(&self) -> bool {
+        self.merge_steps().iter().all(Option::is_some)
+    }
+
+    /// Input leading every state to the same state, None if the Dfa is not synchronizing.
+    /// Found with the greedy algorithm of Eppstein: while more than one state is left,
+    /// apply the shortest input merging some pair of the remaining states.
+    /// The result is not always the shortest one, but it has at most n^3 / 3 alphabets.
+    pub fn synchronizing_word(&self) -> Option<Vec<u64>> {
+        let steps = self.merge_steps();
+        if steps.iter().any(Option::is_none) {
+            return None;
+        }
+
+        let n = self.states as usize;
+        let mut current: Vec<u64> = (0..self.states).collect();
+        let mut word = Vec::new();
+        while current.len() > 1 {
+            let mut shortest: Option<usize> = None;
+            for (i, &p) in current.iter().enumerate() {
+                for &q in &current[i + 1..] {
+                    let pair = pair_index(n, p as usize, q as usize);
+                    let shorter = match shortest {
+                        Some(s) => steps[pair].unwrap().length < steps[s].unwrap().length,
+                        None => true,
+                    };
+                    if shorter {
+                        shortest = Some(pair);
+                    }
+                }
+            }
+            let w = merging_word(&steps, shortest.unwrap());
+            current = current.iter().map(|&q| self.run_from(q, &w)).collect();
+            current.sort_unstable();
+            current.dedup();
+            word.extend(w);
+        }
+        Some(word)
+    }
+
+    /// Input after which the output tells the current state, whatever the initial state.
+    /// The output of an input is whether each visited state accepts, including
+    /// the state before the first alphabet. None if two different states are
+    /// equivalent, so it always exists for a minimal Dfa.
+    pub fn homing_sequence(&self) -> Option<Vec<u64>> {
+        let mut word = Vec::new();
+        loop {
+            // final states reached from initial states with the same output.
+            let mut blocks: HashMap<Vec<bool>, HashSet<u64>> = HashMap::new();
+            for q in 0..self.states {
+                let (output, last) = self.observe(q, &word);
+                blocks.entry(output).or_default().insert(last);
+            }
+            let ambiguous = blocks.values().find(|block| block.len() > 1);
+            match ambiguous {
+                None => return Some(word),
+                Some(block) => {
+                    let mut block: Vec<u64> = block.iter().copied().collect();
+                    block.sort_unstable();
+                    word.extend(self.separating_word(block[0], block[1])?);
+                }
+            }
+        }
+    }
+
+    /// Input whose output tells the initial state, with outputs as in homing_sequence.
+    /// The shortest one is found by breadth first search over groups of states
+    /// that can not be told apart yet, which takes exponential time in the worst case.
+    /// None if there is no such input, which can happen even for a minimal Dfa.
+    pub fn distinguishing_sequence(&self) -> Option<Vec<u64>> {
+        // current states of initial states not told apart yet, grouped by output so far.
+        let initial = self.split(vec![(0..self.states).collect()]);
+        let mut parent: HashMap<Groups, Option<(Groups, u64)>> = HashMap::new();
+        let mut queue = VecDeque::new();
+        parent.insert(initial.clone(), None);
+        queue.push_back(initial);
+
+        while let Some(groups) = queue.pop_front() {
+            if groups.is_empty() {
+                let mut word = Vec::new();
+                let mut current = groups;
+                while let Some(Some((previous, alphabet))) = parent.get(&current) {
+                    word.push(*alphabet);
+                    current = previous.clone();
+                }
+                word.reverse();
+                return Some(word);
+            }
+            'alphabet: for a in 0..self.alphabets {
+                let mut next = Vec::with_capacity(groups.len());
+                for group in &groups {
+                    let mut moved: Vec<u64> =
+                        group.iter().map(|&q| self.transition(q, a)).collect();
+                    moved.sort_unstable();
+                    // two initial states reaching the same state can never be told apart.
+                    if moved.windows(2).any(|w| w[0] == w[1]) {
+                        continue 'alphabet;
+                    }
+                    next.push(moved);
+                }
+                let next = self.split(next);
+                if !parent.contains_key(&next) {
+                    parent.insert(next.clone(), Some((groups.clone(), a)));
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Split groups by acceptance, dropping single states which are told apart,
+    /// and sort them so equal searches share one entry.
+    fn split(&self, groups: Vec<Vec<u64>>) -> Vec<Vec<u64>> {
+        let mut result = Vec::new();
+        for group in groups {
+            let (accept, reject): (Vec<u64>, Vec<u64>) =
+                group.into_iter().partition(|&q| self.is_accept_state(q));
+            result.extend([accept, reject].iter().filter(|g| g.len() > 1).cloned());
+        }
+        result.sort();
+        result
+    }
+
+    /// Shortest input leading p and q to states which differ in acceptance.
+    fn separating_word(&self, p: u64, q: u64) -> Option<Vec<u64>> {
+        let mut parent: HashMap<Pair, Option<(Pair, u64)>> = HashMap::new();
+        let mut queue = VecDeque::new();
+        parent.insert((p, q), None);
+        queue.push_back((p, q));
+        while let Some(pair) = queue.pop_front() {
+            if self.is_accept_state(pair.0) != self.is_accept_state(pair.1) {
+                let mut word = Vec::new();
+                let mut current = pair;
+                while let Some(&Some((previous, alphabet))) = parent.get(&current) {
+                    word.push(alphabet);
+                    current = previous;
+                }
+                word.reverse();
+                return Some(word);
+            }
+            for a in 0..self.alphabets {
+                let next = (self.transition(pair.0, a), self.transition(pair.1, a));
+                parent.entry(next).or_insert_with(|| {
+                    queue.push_back(next);
+                    Some((pair, a))
+                });
+            }
+        }
+        None
+    }
+
+    /// First step of the shortest input merging each pair of states p < q,
+    /// indexed by pair_index, None if the pair can not be merged.
+    /// Found by breadth first search backwards from merged pairs on the pair graph.
+    fn merge_steps(&self) -> Vec<Option<MergeStep>> {
+        let n = self.states as usize;
+        let pairs = n * n.saturating_sub(1) / 2;
+        let mut steps: Vec<Option<MergeStep>> = vec![None; pairs];
+        let mut inverse: Vec<Vec<(usize, u64)>> = vec![Vec::new(); pairs];
+        let mut queue = VecDeque::new();
+        for p in 0..n {
+            for q in p + 1..n {
+                let i = pair_index(n, p, q);
+                for a in 0..self.alphabets {
+                    let pa = self.transition(p as u64, a) as usize;
+                    let qa = self.transition(q as u64, a) as usize;
+                    if pa == qa {
+                        if steps[i].is_none() {
+                            steps[i] = Some(MergeStep {
+                                alphabet: a,
+                                next: None,
+                                length: 1,
+                            });
+                            queue.push_back(i);
+                        }
+                    } else {
+                        inverse[pair_index(n, pa.min(qa), pa.max(qa))].push((i, a));
+                    }
+                }
+            }
+        }
+        while let Some(j) = queue.pop_front() {
+            let length = steps[j].unwrap().length + 1;
+            for &(i, a) in &inverse[j] {
+                if steps[i].is_none() {
+                    steps[i] = Some(MergeStep {
+                        alphabet: a,
+                        next: Some(j),
+                        length,
+                    });
+                    queue.push_back(i);
+                }
+            }
+        }
+        steps
+    }
+
+    /// State reached from state on input, which must be valid.
+    fn run_from(&self, state: u64, input: &[u64]) -> u64 {
+        input.iter().fold(state, |q, &a| self.transition(q, a))
+    }
+
+    /// Acceptance of every visited state and the last state, running input from state.
+    fn observe(&self, state: u64, input: &[u64]) -> (Vec<bool>, u64) {
+        let mut output = vec![self.is_accept_state(state)];
+        let mut q = state;
+        for &a in input {
+            q = self.transition(q, a);
+            output.push(self.is_accept_state(q));
+        }
+        (output, q)
+    }
+}
+
+/// Shortest input merging pair, which must be mergeable, rebuilt from steps.
+fn merging_word(steps: &[Option<MergeStep>], pair: usize) -> Vec<u64> {
+    let mut word = Vec::new();
+    let mut current = Some(pair);
+    while let Some(i) = current {
+        let step = steps[i].unwrap();
+        word.push(step.alphabet);
+        current = step.next;
+    }
+    word
+}
+
+/// Index of the pair of states p < q among n states.
+fn pair_index(n: usize, p: usize, q: usize) -> usize {
+    p * (2 * n - p - 1) / 2 + (q - p - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::{DfaGenerator, Rng};
+
+    // Černý automaton with n states, whose shortest synchronizing word has (n - 1)^2 alphabets.
+    fn cerny(n: u64) -> Dfa {
+        let transition_function = (0..n)
+            .map(|q| vec![(q + 1) % n, if q == 0 { 1 } else { q }])
+            .collect();
+        Dfa::new(n, 2, transition_function, 0, vec![0]).unwrap()
+    }
+
+    #[test]
+    fn test_pair_index() {
+        let n = 5;
+        let mut expected = 0;
+        for p in 0..n {
+            for q in p + 1..n {
+                assert_eq!(pair_index(n, p, q), expected);
+                expected += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn test_synchronizing_word() {
+        for n in 2..8 {
+            let dfa = cerny(n);
+            assert!(dfa.is_synchronizing());
+            let word = dfa.synchronizing_word().unwrap();
+            assert!(word.len() as u64 >= (n - 1) * (n - 1));
+            let target = dfa.run_from(0, &word);
+            assert!((0..n).all(|q| dfa.run_from(q, &word) == target));
+        }
+
+        // permutations never merge states.
+        let parity = Dfa::new(2, 2, vec![vec![0, 1], vec![1, 0]], 0, vec![0]).unwrap();
+        assert!(!parity.is_synchronizing());
+        assert_eq!(parity.synchronizing_word(), None);
+    }
+
+    #[test]
+    fn test_homing_sequence() {
+        let mut rng = Rng::new(24);
+        for _ in 0..30 {
+            let (dfa, _) = DfaGenerator::new(8, 2).generate(&mut rng).minimize();
+            let word = dfa.homing_sequence().unwrap();
+            let mut last_state: HashMap<Vec<bool>, u64> = HashMap::new();
+            for q in 0..dfa.states() {
+                let (output, last) = dfa.observe(q, &word);
+                assert_eq!(*last_state.entry(output).or_insert(last), last);
+            }
+        }
+
+        // states 1 and 2 are equivalent.
+        let redundant = Dfa::new(3, 1, vec![vec![1], vec![2], vec![1]], 0, vec![0]).unwrap();
+        assert_eq!(redundant.homing_sequence(), None);
+    }
+
+    #[test]
+    fn test_distinguishing_sequence() {
+        let mut rng = Rng::new(25);
+        for _ in 0..30 {
+            let (dfa, _) = DfaGenerator::new(6, 2).generate(&mut rng).minimize();
+            if let Some(word) = dfa.distinguishing_sequence() {
+                let outputs: HashSet<Vec<bool>> =
+                    (0..dfa.states()).map(|q| dfa.observe(q, &word).0).collect();
+                assert_eq!(outputs.len() as u64, dfa.states());
+            }
+        }
+
+        let parity = Dfa::new(2, 2, vec![vec![0, 1], vec![1, 0]], 0, vec![0]).unwrap();
+        assert_eq!(parity.distinguishing_sequence(), Some(vec![]));
+
+        // minimal, but 0 and 1 merge on 0 and 1 and 2 merge on 1 while all three accept.
+        let merging = Dfa::new(
+            4,
+            2,
+            vec![vec![1, 2], vec![1, 3], vec![0, 3], vec![2, 0]],
+            0,
+            vec![0, 1, 2],
+        )
+        .unwrap();
+        assert_eq!(merging.minimize().0.states(), 4);
+        assert_eq!(merging.distinguishing_sequence(), None);
+        assert!(merging.homing_sequence().is_some());
+    }
+}
@@ -0,0 +1,222 @@
+use crate::dfa::Dfa;
+use crate::nfa::Nfa;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Graph of an automaton ready to be rendered.
+/// Parallel edges between the same pair of states are merged into one edge
+/// labelled with every alphabet on it.
+pub(crate) struct Diagram {
+    states: u64,
+    initial_states: Vec<u64>,
+    accept_states: Vec<u64>,
+    edges: BTreeMap<(u64, u64), Vec<String>>,
+}
+
+impl Diagram {
+    pub(crate) fn new(states: u64) -> Self {
+        Diagram {
+            states,
+            initial_states: Vec::new(),
+            accept_states: Vec::new(),
+            edges: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn initial(&mut self, state: u64) {
+        self.initial_states.push(state);
+    }
+
+    pub(crate) fn accept(&mut self, state: u64) {
+        self.accept_states.push(state);
+    }
+
+    pub(crate) fn edge(&mut self, from: u64, to: u64, label: String) {
+        let labels = self.edges.entry((from, to)).or_default();
+        if !labels.contains(&label) {
+            labels.push(label);
+        }
+    }
+
+    pub(crate) fn dot(&self, name: &str) -> String {
+        let mut out = String::new();
+        writeln!(out, "digraph {} {{", name).unwrap();
+        writeln!(out, "    rankdir=LR;").unwrap();
+        for (i, q) in self.initial_states.iter().enumerate() {
+            writeln!(out, "    __start{} [shape=point];", i).unwrap();
+            writeln!(out, "    __start{} -> {};", i, q).unwrap();
+        }
+        for q in 0..self.states {
+            let shape = if self.accept_states.contains(&q) {
+                "doublecircle"
+            } else {
+                "circle"
+            };
+            writeln!(out, "    {} [shape={}];", q, shape).unwrap();
+        }
+        for ((from, to), labels) in &self.edges {
+            writeln!(
+                out,
+                "    {} -> {} [label=\"{}\"];",
+                from,
+                to,
+                labels.join(", ")
+            )
+            .unwrap();
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    pub(crate) fn mermaid(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "stateDiagram-v2").unwrap();
+        writeln!(out, "    classDef accept stroke-width:4px").unwrap();
+        for q in &self.initial_states {
+            writeln!(out, "    [*] --> q{}", q).unwrap();
+        }
+        for ((from, to), labels) in &self.edges {
+            writeln!(out, "    q{} --> q{} : {}", from, to, labels.join(", ")).unwrap();
+        }
+        for q in &self.accept_states {
+            writeln!(out, "    q{} --> [*]", q).unwrap();
+            writeln!(out, "    class q{} accept", q).unwrap();
+        }
+        out
+    }
+}
+
+impl Dfa {
+    fn diagram(&self) -> Diagram {
+        let mut diagram = Diagram::new(self.states());
+        diagram.initial(self.initial_state());
+        for q in 0..self.states() {
+            if self.is_accept_state(q) {
+                diagram.accept(q);
+            }
+            for a in 0..self.alphabets() {
+                let next = self.next_state(q, a).unwrap();
+                diagram.edge(q, next, a.to_string());
+            }
+        }
+        diagram
+    }
+
+    /// Graphviz DOT source of this Dfa.
+    /// Accept states are drawn as double circles and the initial state
+    /// has an incoming arrow from an invisible point.
+    pub fn to_dot(&self) -> String {
+        self.diagram().dot("dfa")
+    }
+
+    /// Mermaid state diagram of this Dfa.
+    /// The initial state comes from [*], accept states go to [*]
+    /// and are styled with the accept class.
+    pub fn to_mermaid(&self) -> String {
+        self.diagram().mermaid()
+    }
+}
+
+impl Nfa {
+    fn diagram(&self) -> Diagram {
+        let mut diagram = Diagram::new(self.states());
+        for q in 0..self.states() {
+            if self.is_initial_state(q) {
+                diagram.initial(q);
+            }
+            if self.is_accept_state(q) {
+                diagram.accept(q);
+            }
+            for a in 0..self.alphabets() {
+                for &next in self.next_states(q, a) {
+                    diagram.edge(q, next, a.to_string());
+                }
+            }
+            for &next in self.epsilon_states(q) {
+                diagram.edge(q, next, "ε".to_string());
+            }
+        }
+        diagram
+    }
+
+    /// Graphviz DOT source of this Nfa, drawn like Dfa::to_dot
+    /// with an arrow for each initial state and ε on epsilon transitions.
+    pub fn to_dot(&self) -> String {
+        self.diagram().dot("nfa")
+    }
+
+    /// Mermaid state diagram of this Nfa, drawn like Dfa::to_mermaid.
+    pub fn to_mermaid(&self) -> String {
+        self.diagram().mermaid()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dfa() -> Dfa {
+        Dfa::new(2, 3, vec![vec![1, 1, 0], vec![0, 1, 1]], 0, vec![1]).unwrap()
+    }
+
+    #[test]
+    fn test_dot() {
+        let expected = "digraph dfa {
+    rankdir=LR;
+    __start0 [shape=point];
+    __start0 -> 0;
+    0 [shape=circle];
+    1 [shape=doublecircle];
+    0 -> 0 [label=\"2\"];
+    0 -> 1 [label=\"0, 1\"];
+    1 -> 0 [label=\"0\"];
+    1 -> 1 [label=\"1, 2\"];
+}
+";
+        assert_eq!(dfa().to_dot(), expected);
+    }
+
+    #[test]
+    fn test_mermaid() {
+        let expected = "stateDiagram-v2
+    classDef accept stroke-width:4px
+    [*] --> q0
+    q0 --> q0 : 2
+    q0 --> q1 : 0, 1
+    q1 --> q0 : 0
+    q1 --> q1 : 1, 2
+    q1 --> [*]
+    class q1 accept
+";
+        assert_eq!(dfa().to_mermaid(), expected);
+    }
+
+    #[test]
+    fn test_nfa() {
+        let nfa = Nfa::new(
+            2,
+            1,
+            vec![vec![vec![0, 1]], vec![vec![]]],
+            vec![vec![1], vec![]],
+            vec![0, 1],
+            vec![1],
+        )
+        .unwrap();
+        let expected = "digraph nfa {
+    rankdir=LR;
+    __start0 [shape=point];
+    __start0 -> 0;
+    __start1 [shape=point];
+    __start1 -> 1;
+    0 [shape=circle];
+    1 [shape=doublecircle];
+    0 -> 0 [label=\"0\"];
+    0 -> 1 [label=\"0, ε\"];
+}
+";
+        assert_eq!(nfa.to_dot(), expected);
+        assert!(nfa
+            .to_mermaid()
+            .contains("    [*] --> q1\n    q0 --> q0 : 0\n"));
+    }
+}
@@ -0,0 +1,438 @@
+use crate::dfa::Dfa;
+use crate::random::Rng;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::error::Error;
+use std::fmt;
+
+/// Errors returned by LStar::learn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LearnError {
+    /// the oracle gave an input on which the hypothesis already agrees with
+    /// the membership queries, or which has an alphabet out of range.
+    /// Learning can not progress with it.
+    InvalidCounterexample(Vec<u64>),
+}
+
+impl fmt::Display for LearnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LearnError::InvalidCounterexample(word) => write!(
+                f,
+                "counterexample {:?} does not separate the hypothesis from the target",
+                word
+            ),
+        }
+    }
+}
+
+impl Error for LearnError {}
+
+/// Oracle answering equivalence queries of LStar.
+/// Any FnMut(&Dfa) -> Option<Vec<u64>> closure can be used as an oracle.
+pub trait EquivalenceOracle {
+    /// Input on which hypothesis and the target language disagree,
+    /// None if the hypothesis is considered correct.
+    fn counterexample(&mut self, hypothesis: &Dfa) -> Option<Vec<u64>>;
+}
+
+impl<F> EquivalenceOracle for F
+where
+    F: FnMut(&Dfa) -> Option<Vec<u64>>,
+{
+    fn counterexample(&mut self, hypothesis: &Dfa) -> Option<Vec<u64>> {
+        self(hypothesis)
+    }
+}
+
+/// Angluin's L* algorithm learning a minimal Dfa of an unknown regular language
+/// from membership queries and equivalence queries.
+/// Counterexamples are handled as proposed by Maler and Pnueli, adding every
+/// suffix of a counterexample to the experiments, which keeps the table consistent.
+pub struct LStar<M> {
+    alphabets: u64,
+    membership: M,
+    cache: HashMap<Vec<u64>, bool>,
+    membership_queries: usize,
+    equivalence_queries: usize,
+}
+
+impl<M> LStar<M>
+where
+    M: FnMut(&[u64]) -> bool,
+{
+    /// membership answers whether an input over alphabets [0, alphabets) is in the language.
+    pub fn new(alphabets: u64, membership: M) -> Self {
+        LStar {
+            alphabets,
+            membership,
+            cache: HashMap::new(),
+            membership_queries: 0,
+            equivalence_queries: 0,
+        }
+    }
+
+    /// Number of distinct inputs asked to the membership closure.
+    pub fn membership_queries(&self) -> usize {
+        self.membership_queries
+    }
+
+    /// Number of hypotheses given to the equivalence oracle.
+    pub fn equivalence_queries(&self) -> usize {
+        self.equivalence_queries
+    }
+
+    fn member(&mut self, word: Vec<u64>) -> bool {
+        if let Some(&res) = self.cache.get(&word) {
+            return res;
+        }
+        self.membership_queries += 1;
+        let res = (self.membership)(&word);
+        self.cache.insert(word, res);
+        res
+    }
+
+    fn row(&mut self, prefix: &[u64], suffixes: &[Vec<u64>]) -> Vec<bool> {
+        suffixes
+            .iter()
+            .map(|suffix| {
+                let mut word = prefix.to_vec();
+                word.extend_from_slice(suffix);
+                self.member(word)
+            })
+            .collect()
+    }
+
+    /// Learn the language until the oracle finds no counterexample.
+    /// Every counterexample is checked with a membership query, and one the
+    /// hypothesis already answers correctly stops learning with an error.
+    pub fn learn<E: EquivalenceOracle>(&mut self, oracle: &mut E) -> Result<Dfa, LearnError> {
+        // prefixes are access words of states and suffixes are experiments.
+        let mut prefixes: Vec<Vec<u64>> = vec![vec![]];
+        let mut suffixes: Vec<Vec<u64>> = vec![vec![]];
+
+        loop {
+            // make the table closed: every row of s a must be the row of some prefix.
+            let mut rows: Vec<Vec<bool>>;
+            let mut transitions: Vec<Vec<usize>>;
+            'close: loop {
+                rows = Vec::new();
+                for prefix in &prefixes {
+                    rows.push(self.row(prefix, &suffixes));
+                }
+                transitions = Vec::new();
+                for i in 0..prefixes.len() {
+                    let mut row = Vec::new();
+                    for a in 0..self.alphabets {
+                        let mut extended = prefixes[i].clone();
+                        extended.push(a);
+                        let extended_row = self.row(&extended, &suffixes);
+                        match rows.iter().position(|r| r == &extended_row) {
+                            Some(state) => row.push(state),
+                            None => {
+                                prefixes.push(extended);
+                                continue 'close;
+                            }
+                        }
+                    }
+                    transitions.push(row);
+                }
+                break;
+            }
+
+            let accept_states = (0..prefixes.len() as u64)
+                .filter(|&q| rows[q as usize][0])
+                .collect();
+            let transition_function = transitions
+                .into_iter()
+                .map(|row| row.into_iter().map(|q| q as u64).collect())
+                .collect();
+            let hypothesis = Dfa::new(
+                prefixes.len() as u64,
+                self.alphabets,
+                transition_function,
+                0,
+                accept_states,
+            )
+            .unwrap();
+
+            self.equivalence_queries += 1;
+            match oracle.counterexample(&hypothesis) {
+                None => return Ok(hypothesis.minimize().0),
+                Some(counterexample) => {
+                    let valid = counterexample.iter().all(|&a| a < self.alphabets)
+                        && self.member(counterexample.clone())
+                            != hypothesis.is_language(counterexample.clone());
+                    if !valid {
+                        return Err(LearnError::InvalidCounterexample(counterexample));
+                    }
+                    for i in 0..counterexample.len() {
+                        let suffix = counterexample[i..].to_vec();
+                        if !suffixes.contains(&suffix) {
+                            suffixes.push(suffix);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Equivalence oracle comparing the hypothesis with target on random inputs.
+pub struct RandomWalkOracle<M> {
+    target: M,
+    walks: usize,
+    max_length: usize,
+    rng: Rng,
+    queries: usize,
+}
+
+impl<M> RandomWalkOracle<M>
+where
+    M: FnMut(&[u64]) -> bool,
+{
+    /// Try walks random inputs with length up to max_length for every hypothesis.
+    pub fn new(target: M, walks: usize, max_length: usize, seed: u64) -> Self {
+        RandomWalkOracle {
+            target,
+            walks,
+            max_length,
+            rng: Rng::new(seed),
+            queries: 0,
+        }
+    }
+
+    /// Number of inputs asked to target so far.
+    pub fn queries(&self) -> usize {
+        self.queries
+    }
+}
+
+impl<M> EquivalenceOracle for RandomWalkOracle<M>
+where
+    M: FnMut(&[u64]) -> bool,
+{
+    fn counterexample(&mut self, hypothesis: &Dfa) -> Option<Vec<u64>> {
+        for _ in 0..self.walks {
+            // without alphabets, the empty input is the only one.
+            let length = if hypothesis.alphabets() == 0 {
+                0
+            } else {
+                self.rng.below(self.max_length as u64 + 1)
+            };
+            let word: Vec<u64> = (0..length)
+                .map(|_| self.rng.below(hypothesis.alphabets()))
+                .collect();
+            self.queries += 1;
+            if hypothesis.is_language(word.clone()) != (self.target)(&word) {
+                return Some(word);
+            }
+        }
+        None
+    }
+}
+
+/// Equivalence oracle based on the W-method.
+/// It tests every input p m w where p reaches a state or takes one more transition,
+/// m is any input of length up to extra_states and w distinguishes two states
+/// of the hypothesis. This finds a counterexample whenever the target has at most
+/// extra_states more states than the hypothesis.
+pub struct WMethodOracle<M> {
+    target: M,
+    extra_states: usize,
+    queries: usize,
+}
+
+impl<M> WMethodOracle<M>
+where
+    M: FnMut(&[u64]) -> bool,
+{
+    pub fn new(target: M, extra_states: usize) -> Self {
+        WMethodOracle {
+            target,
+            extra_states,
+            queries: 0,
+        }
+    }
+
+    /// Number of inputs asked to target so far.
+    pub fn queries(&self) -> usize {
+        self.queries
+    }
+}
+
+impl<M> EquivalenceOracle for WMethodOracle<M>
+where
+    M: FnMut(&[u64]) -> bool,
+{
+    fn counterexample(&mut self, hypothesis: &Dfa) -> Option<Vec<u64>> {
+        let alphabets = hypothesis.alphabets();
+        let access = access_words(hypothesis);
+
+        // transition cover: access words and access words followed by one alphabet.
+        let mut cover: Vec<Vec<u64>> = access.clone();
+        for word in &access {
+            for a in 0..alphabets {
+                let mut extended = word.clone();
+                extended.push(a);
+                cover.push(extended);
+            }
+        }
+
+        // characterization set: a distinguishing word for every pair of states.
+        let mut characterization: HashSet<Vec<u64>> = HashSet::new();
+        characterization.insert(vec![]);
+        for p in 0..hypothesis.states() {
+            for q in p + 1..hypothesis.states() {
+                let from_p = with_initial_state(hypothesis, p);
+                let from_q = with_initial_state(hypothesis, q);
+                if let Err(word) = from_p.equivalent(&from_q) {
+                    characterization.insert(word);
+                }
+            }
+        }
+
+        // middle parts of length 0 to extra_states.
+        let mut middles: Vec<Vec<u64>> = vec![vec![]];
+        let mut layer: Vec<Vec<u64>> = vec![vec![]];
+        for _ in 0..self.extra_states {
+            layer = layer
+                .iter()
+                .flat_map(|word| {
+                    (0..alphabets).map(move |a| {
+                        let mut extended = word.clone();
+                        extended.push(a);
+                        extended
+                    })
+                })
+                .collect();
+            middles.extend(layer.iter().cloned());
+        }
+
+        for p in &cover {
+            for m in &middles {
+                for w in &characterization {
+                    let word: Vec<u64> = p.iter().chain(m).chain(w).copied().collect();
+                    self.queries += 1;
+                    if hypothesis.is_language(word.clone()) != (self.target)(&word) {
+                        return Some(word);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Shortest input reaching each reachable state of dfa.
+fn access_words(dfa: &Dfa) -> Vec<Vec<u64>> {
+    let mut words: Vec<Option<Vec<u64>>> = vec![None; dfa.states() as usize];
+    let mut queue = VecDeque::new();
+    words[dfa.initial_state() as usize] = Some(vec![]);
+    queue.push_back(dfa.initial_state());
+    while let Some(q) = queue.pop_front() {
+        for a in 0..dfa.alphabets() {
+            let next = dfa.next_state(q, a).unwrap();
+            if words[next as usize].is_none() {
+                let mut word = words[q as usize].clone().unwrap();
+                word.push(a);
+                words[next as usize] = Some(word);
+                queue.push_back(next);
+            }
+        }
+    }
+    words.into_iter().flatten().collect()
+}
+
+/// Copy of dfa starting from state.
+fn with_initial_state(dfa: &Dfa, state: u64) -> Dfa {
+    let transition_function = (0..dfa.states())
+        .map(|q| {
+            (0..dfa.alphabets())
+                .map(|a| dfa.next_state(q, a).unwrap())
+                .collect()
+        })
+        .collect();
+    let accept_states = (0..dfa.states())
+        .filter(|&q| dfa.is_accept_state(q))
+        .collect();
+    Dfa::new(
+        dfa.states(),
+        dfa.alphabets(),
+        transition_function,
+        state,
+        accept_states,
+    )
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // inputs over {0, 1} whose third symbol from the end is 1, minimal Dfa has 8 states.
+    fn third_from_end(word: &[u64]) -> bool {
+        word.len() >= 3 && word[word.len() - 3] == 1
+    }
+
+    #[test]
+    fn test_learn_with_exact_oracle() {
+        // accepts inputs with number of 1 divisible by 3.
+        let target = Dfa::new(3, 2, vec![vec![0, 1], vec![1, 2], vec![2, 0]], 0, vec![0]).unwrap();
+        let mut learner = LStar::new(2, |word: &[u64]| target.is_language(word.to_vec()));
+        let mut oracle = |hypothesis: &Dfa| hypothesis.equivalent(&target).err();
+        let learned = learner.learn(&mut oracle).unwrap();
+
+        assert_eq!(learned.states(), 3);
+        assert_eq!(learned.equivalent(&target), Ok(()));
+        assert!(learner.membership_queries() > 0);
+        assert!(learner.equivalence_queries() >= 1);
+    }
+
+    #[test]
+    fn test_invalid_counterexample() {
+        let mut learner = LStar::new(2, third_from_end);
+        // the first hypothesis rejects everything, which is right for [1, 1].
+        let mut oracle = |_: &Dfa| Some(vec![1, 1]);
+        assert_eq!(
+            learner.learn(&mut oracle).unwrap_err(),
+            LearnError::InvalidCounterexample(vec![1, 1])
+        );
+        let mut oracle = |_: &Dfa| Some(vec![2]);
+        assert_eq!(
+            learner.learn(&mut oracle).unwrap_err(),
+            LearnError::InvalidCounterexample(vec![2])
+        );
+    }
+
+    #[test]
+    fn test_no_alphabets() {
+        let mut learner = LStar::new(0, |word: &[u64]| word.is_empty());
+        let mut oracle = RandomWalkOracle::new(|word: &[u64]| word.is_empty(), 10, 5, 1);
+        let learned = learner.learn(&mut oracle).unwrap();
+        assert_eq!(learned.states(), 1);
+        assert!(learned.is_language(vec![]));
+    }
+
+    #[test]
+    fn test_learn_with_random_walk() {
+        let mut learner = LStar::new(2, third_from_end);
+        let mut oracle = RandomWalkOracle::new(third_from_end, 1000, 12, 42);
+        let learned = learner.learn(&mut oracle).unwrap();
+
+        assert_eq!(learned.states(), 8);
+        assert!(learned.is_language(vec![0, 1, 0, 0]));
+        assert!(!learned.is_language(vec![1, 0, 0, 0]));
+        assert!(oracle.queries() >= 1000);
+    }
+
+    #[test]
+    fn test_learn_with_w_method() {
+        let mut learner = LStar::new(2, third_from_end);
+        let mut oracle = WMethodOracle::new(third_from_end, 2);
+        let learned = learner.learn(&mut oracle).unwrap();
+
+        assert_eq!(learned.states(), 8);
+        assert!(learned.is_language(vec![1, 1, 1]));
+        assert!(!learned.is_language(vec![1, 1]));
+    }
+}
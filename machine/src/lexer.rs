@@ -0,0 +1,615 @@
+use crate::dfa::Dfa;
+use crate::regex::Regex;
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::fmt;
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+/// Lexer built from an ordered list of (token kind, pattern) rules.
+///
+/// Patterns support literal characters, `.` (any character but a newline),
+/// classes like `[a-z_]` and `[^0-9]`, escapes `\d`, `\w`, `\s`, `\n`, `\t`, `\r`
+/// and `\` followed by any other character for that character, grouping with
+/// parentheses, alternation `|` and repetitions `*`, `+` and `?`.
+///
+/// Every rule is compiled into one Dfa whose alphabets are classes of characters
+/// which no pattern tells apart. Each state is tagged with the first rule matching
+/// there, so earlier rules win when several match the same text.
+#[derive(Debug, Clone)]
+pub struct Lexer<K> {
+    kinds: Vec<K>,
+    dfa: Dfa,
+    // rule matched in each state of dfa.
+    tags: Vec<Option<usize>>,
+    live: Vec<bool>,
+    // classes[i] starts at boundaries[i] and ends before boundaries[i + 1].
+    boundaries: Vec<u32>,
+}
+
+/// Builder of Lexer, rules added first have higher priority.
+#[derive(Debug, Clone)]
+pub struct LexerBuilder<K> {
+    rules: Vec<(K, String)>,
+}
+
+/// Token produced by Lexer::tokenize.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token<'a, K> {
+    pub kind: K,
+    pub text: &'a str,
+    pub span: Span,
+}
+
+/// Position of a token in the input.
+/// start and end are byte offsets, line and column of the start count from 1
+/// and column counts characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LexError {
+    /// pattern of rule is malformed at byte offset position.
+    Pattern {
+        rule: usize,
+        position: usize,
+        message: String,
+    },
+    /// no rule matches a non empty text at this position of the input.
+    NoMatch {
+        offset: usize,
+        line: usize,
+        column: usize,
+    },
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LexError::Pattern {
+                rule,
+                position,
+                message,
+            } => write!(
+                f,
+                "invalid pattern of rule {} at {}: {}",
+                rule, position, message
+            ),
+            LexError::NoMatch { line, column, .. } => {
+                write!(f, "no token matches at {}:{}", line, column)
+            }
+        }
+    }
+}
+
+impl Error for LexError {}
+
+impl<K: Clone> Lexer<K> {
+    pub fn builder() -> LexerBuilder<K> {
+        LexerBuilder { rules: Vec::new() }
+    }
+
+    /// Combined Dfa over character classes.
+    pub fn dfa(&self) -> &Dfa {
+        &self.dfa
+    }
+
+    fn class_of(&self, c: char) -> u64 {
+        let c = c as u32;
+        match self.boundaries.binary_search(&c) {
+            Ok(i) => i as u64,
+            Err(i) => i as u64 - 1,
+        }
+    }
+
+    /// Split input into tokens, always taking the longest match (maximal munch).
+    pub fn tokenize<'a>(&self, input: &'a str) -> Result<Vec<Token<'a, K>>, LexError> {
+        let mut tokens = Vec::new();
+        let mut start = 0;
+        let mut line = 1;
+        let mut column = 1;
+
+        while start < input.len() {
+            let mut state = self.dfa.initial_state();
+            let mut last_match = None;
+            for (i, c) in input[start..].char_indices() {
+                state = self.dfa.next_state(state, self.class_of(c)).unwrap();
+                if let Some(rule) = self.tags[state as usize] {
+                    last_match = Some((rule, start + i + c.len_utf8()));
+                }
+                if !self.live[state as usize] {
+                    break;
+                }
+            }
+
+            let (rule, end) = last_match.ok_or(LexError::NoMatch {
+                offset: start,
+                line,
+                column,
+            })?;
+            let text = &input[start..end];
+            tokens.push(Token {
+                kind: self.kinds[rule].clone(),
+                text,
+                span: Span {
+                    start,
+                    end,
+                    line,
+                    column,
+                },
+            });
+            for c in text.chars() {
+                if c == '\n' {
+                    line += 1;
+                    column = 1;
+                } else {
+                    column += 1;
+                }
+            }
+            start = end;
+        }
+        Ok(tokens)
+    }
+}
+
+impl<K: Clone> LexerBuilder<K> {
+    pub fn rule(mut self, kind: K, pattern: &str) -> Self {
+        self.rules.push((kind, pattern.to_string()));
+        self
+    }
+
+    pub fn build(self) -> Result<Lexer<K>, LexError> {
+        let mut patterns = Vec::new();
+        for (rule, (_, pattern)) in self.rules.iter().enumerate() {
+            patterns.push(PatternParser::new(pattern, rule).parse()?);
+        }
+
+        // split characters into classes at every boundary of a range used by some pattern.
+        let mut boundaries = vec![0];
+        for pattern in &patterns {
+            pattern.boundaries(&mut boundaries);
+        }
+        boundaries.sort_unstable();
+        boundaries.dedup();
+        let alphabets = boundaries.len() as u64;
+
+        let start: Vec<Regex> = patterns.iter().map(|p| p.to_regex(&boundaries)).collect();
+
+        // Brzozowski construction: each state is the vector of derivatives of every rule.
+        let mut ids: HashMap<Vec<Regex>, u64> = HashMap::new();
+        let mut states = vec![start.clone()];
+        let mut queue = VecDeque::new();
+        ids.insert(start.clone(), 0);
+        queue.push_back(start);
+        let mut transition_function = Vec::new();
+        while let Some(state) = queue.pop_front() {
+            let row = (0..alphabets)
+                .map(|a| {
+                    let next: Vec<Regex> = state.iter().map(|r| r.derivative(a)).collect();
+                    *ids.entry(next.clone()).or_insert_with(|| {
+                        states.push(next.clone());
+                        queue.push_back(next);
+                        states.len() as u64 - 1
+                    })
+                })
+                .collect();
+            transition_function.push(row);
+        }
+
+        let tags: Vec<Option<usize>> = states
+            .iter()
+            .map(|state| state.iter().position(Regex::is_nullable))
+            .collect();
+        let accept_states = (0..states.len() as u64)
+            .filter(|&q| tags[q as usize].is_some())
+            .collect();
+        let dfa = Dfa::new(
+            states.len() as u64,
+            alphabets,
+            transition_function,
+            0,
+            accept_states,
+        )
+        .unwrap();
+
+        Ok(Lexer {
+            kinds: self.rules.into_iter().map(|(kind, _)| kind).collect(),
+            live: dfa.live_states(),
+            dfa,
+            tags,
+            boundaries,
+        })
+    }
+}
+
+/// Parsed pattern where atoms are sets of character ranges.
+#[derive(Debug, Clone)]
+enum Pattern {
+    Epsilon,
+    // inclusive ranges of code points.
+    Chars(Vec<(u32, u32)>),
+    Concat(Vec<Pattern>),
+    Alt(Vec<Pattern>),
+    Star(Box<Pattern>),
+}
+
+const MAX_CHAR: u32 = 0x10ffff;
+
+impl Pattern {
+    fn boundaries(&self, out: &mut Vec<u32>) {
+        match self {
+            Pattern::Epsilon => {}
+            Pattern::Chars(ranges) => {
+                for &(lo, hi) in ranges {
+                    out.push(lo);
+                    if hi < MAX_CHAR {
+                        out.push(hi + 1);
+                    }
+                }
+            }
+            Pattern::Concat(v) | Pattern::Alt(v) => v.iter().for_each(|p| p.boundaries(out)),
+            Pattern::Star(p) => p.boundaries(out),
+        }
+    }
+
+    fn to_regex(&self, boundaries: &[u32]) -> Regex {
+        match self {
+            Pattern::Epsilon => Regex::Epsilon,
+            Pattern::Chars(ranges) => (0..boundaries.len())
+                .filter(|&i| {
+                    let c = boundaries[i];
+                    ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi)
+                })
+                .map(|i| Regex::symbol(i as u64))
+                .fold(Regex::Empty, Regex::alt),
+            Pattern::Concat(v) => v
+                .iter()
+                .map(|p| p.to_regex(boundaries))
+                .fold(Regex::Epsilon, Regex::concat),
+            Pattern::Alt(v) => v
+                .iter()
+                .map(|p| p.to_regex(boundaries))
+                .fold(Regex::Empty, Regex::alt),
+            Pattern::Star(p) => Regex::star(p.to_regex(boundaries)),
+        }
+    }
+}
+
+/// Recursive descent parser of the pattern syntax described on Lexer.
+struct PatternParser<'a> {
+    chars: Peekable<CharIndices<'a>>,
+    len: usize,
+    rule: usize,
+}
+
+impl<'a> PatternParser<'a> {
+    fn new(pattern: &'a str, rule: usize) -> Self {
+        PatternParser {
+            chars: pattern.char_indices().peekable(),
+            len: pattern.len(),
+            rule,
+        }
+    }
+
+    fn error<T>(&mut self, message: &str) -> Result<T, LexError> {
+        let position = self.chars.peek().map_or(self.len, |&(i, _)| i);
+        Err(LexError::Pattern {
+            rule: self.rule,
+            position,
+            message: message.to_string(),
+        })
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|&(_, c)| c)
+    }
+
+    fn parse(mut self) -> Result<Pattern, LexError> {
+        let pattern = self.alt()?;
+        match self.peek() {
+            None => Ok(pattern),
+            Some(_) => self.error("unmatched ')'"),
+        }
+    }
+
+    fn alt(&mut self) -> Result<Pattern, LexError> {
+        let mut branches = vec![self.concat()?];
+        while self.peek() == Some('|') {
+            self.chars.next();
+            branches.push(self.concat()?);
+        }
+        Ok(if branches.len() == 1 {
+            branches.pop().unwrap()
+        } else {
+            Pattern::Alt(branches)
+        })
+    }
+
+    fn concat(&mut self) -> Result<Pattern, LexError> {
+        let mut items = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            items.push(self.repeat()?);
+        }
+        Ok(match items.len() {
+            0 => Pattern::Epsilon,
+            1 => items.pop().unwrap(),
+            _ => Pattern::Concat(items),
+        })
+    }
+
+    fn repeat(&mut self) -> Result<Pattern, LexError> {
+        let mut p = self.atom()?;
+        while let Some(c) = self.peek() {
+            p = match c {
+                '*' => Pattern::Star(Box::new(p)),
+                '+' => Pattern::Concat(vec![p.clone(), Pattern::Star(Box::new(p))]),
+                '?' => Pattern::Alt(vec![p, Pattern::Epsilon]),
+                _ => break,
+            };
+            self.chars.next();
+        }
+        Ok(p)
+    }
+
+    fn atom(&mut self) -> Result<Pattern, LexError> {
+        match self.peek() {
+            Some('(') => {
+                self.chars.next();
+                let p = self.alt()?;
+                if self.peek() != Some(')') {
+                    return self.error("expected ')'");
+                }
+                self.chars.next();
+                Ok(p)
+            }
+            Some('[') => {
+                self.chars.next();
+                self.class()
+            }
+            Some('.') => {
+                self.chars.next();
+                Ok(Pattern::Chars(complement(&[('\n' as u32, '\n' as u32)])))
+            }
+            Some('\\') => {
+                self.chars.next();
+                self.escape()
+            }
+            Some('*') | Some('+') | Some('?') => self.error("nothing to repeat"),
+            Some(c) => {
+                self.chars.next();
+                Ok(Pattern::Chars(vec![(c as u32, c as u32)]))
+            }
+            None => self.error("unexpected end of pattern"),
+        }
+    }
+
+    fn escape(&mut self) -> Result<Pattern, LexError> {
+        let c = match self.chars.next() {
+            Some((_, c)) => c,
+            None => return self.error("unexpected end of pattern"),
+        };
+        Ok(Pattern::Chars(escape_ranges(c)))
+    }
+
+    fn class(&mut self) -> Result<Pattern, LexError> {
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.chars.next();
+        }
+        let mut ranges = Vec::new();
+        loop {
+            let lo = match self.chars.next() {
+                Some((_, ']')) if !ranges.is_empty() => break,
+                Some((_, '\\')) => match self.chars.next() {
+                    Some((_, c)) => {
+                        let escaped = escape_ranges(c);
+                        if escaped.len() != 1 || escaped[0].0 != escaped[0].1 {
+                            ranges.extend(escaped);
+                            continue;
+                        }
+                        escaped[0].0
+                    }
+                    None => return self.error("unterminated class"),
+                },
+                Some((_, c)) => c as u32,
+                None => return self.error("unterminated class"),
+            };
+            let mut lookahead = self.chars.clone();
+            match (lookahead.next(), lookahead.peek()) {
+                (Some((_, '-')), Some(&(_, hi))) if hi != ']' => {
+                    self.chars.next();
+                    self.chars.next();
+                    if (hi as u32) < lo {
+                        return self.error("range out of order");
+                    }
+                    ranges.push((lo, hi as u32));
+                }
+                _ => ranges.push((lo, lo)),
+            }
+        }
+        Ok(Pattern::Chars(if negated {
+            complement(&ranges)
+        } else {
+            ranges
+        }))
+    }
+}
+
+/// Ranges matched by \c.
+fn escape_ranges(c: char) -> Vec<(u32, u32)> {
+    let r = |lo: char, hi: char| (lo as u32, hi as u32);
+    match c {
+        'd' => vec![r('0', '9')],
+        'w' => vec![r('0', '9'), r('A', 'Z'), r('_', '_'), r('a', 'z')],
+        's' => vec![r('\t', '\n'), r('\r', '\r'), r(' ', ' ')],
+        'n' => vec![r('\n', '\n')],
+        't' => vec![r('\t', '\t')],
+        'r' => vec![r('\r', '\r')],
+        c => vec![r(c, c)],
+    }
+}
+
+/// Every code point not in ranges.
+fn complement(ranges: &[(u32, u32)]) -> Vec<(u32, u32)> {
+    let mut sorted = ranges.to_vec();
+    sorted.sort_unstable();
+    let mut out = Vec::new();
+    let mut next = 0;
+    for (lo, hi) in sorted {
+        if lo > next {
+            out.push((next, lo - 1));
+        }
+        next = next.max(hi + 1);
+    }
+    if next <= MAX_CHAR {
+        out.push((next, MAX_CHAR));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Kind {
+        If,
+        Ident,
+        Number,
+        Op,
+        Space,
+    }
+
+    fn lexer() -> Lexer<Kind> {
+        Lexer::builder()
+            .rule(Kind::If, "if")
+            .rule(Kind::Ident, "[a-zA-Z_]\\w*")
+            .rule(Kind::Number, "\\d+(\\.\\d+)?")
+            .rule(Kind::Op, "==|=|\\+|-")
+            .rule(Kind::Space, "\\s+")
+            .build()
+            .unwrap()
+    }
+
+    fn kinds<'a>(tokens: &[Token<'a, Kind>]) -> Vec<(Kind, &'a str)> {
+        tokens.iter().map(|t| (t.kind, t.text)).collect()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let tokens = lexer().tokenize("if iffy == 3.14").unwrap();
+        assert_eq!(
+            kinds(&tokens),
+            vec![
+                (Kind::If, "if"),
+                (Kind::Space, " "),
+                (Kind::Ident, "iffy"),
+                (Kind::Space, " "),
+                (Kind::Op, "=="),
+                (Kind::Space, " "),
+                (Kind::Number, "3.14"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_maximal_munch_backtracks() {
+        // "1." is not a number, so the longest match is "1" followed by an error on ".".
+        let err = lexer().tokenize("x = 1.").unwrap_err();
+        assert_eq!(
+            err,
+            LexError::NoMatch {
+                offset: 5,
+                line: 1,
+                column: 6
+            }
+        );
+    }
+
+    #[test]
+    fn test_spans() {
+        let tokens = lexer().tokenize("a\n  b = 2").unwrap();
+        assert_eq!(tokens[1].text, "\n  ");
+        assert_eq!(tokens[2].text, "b");
+        assert_eq!(
+            tokens[2].span,
+            Span {
+                start: 4,
+                end: 5,
+                line: 2,
+                column: 3
+            }
+        );
+    }
+
+    #[test]
+    fn test_no_match_position() {
+        let err = lexer().tokenize("a = 1\nb = $").unwrap_err();
+        assert_eq!(
+            err,
+            LexError::NoMatch {
+                offset: 10,
+                line: 2,
+                column: 5
+            }
+        );
+    }
+
+    #[test]
+    fn test_classes_and_dot() {
+        let lexer = Lexer::builder()
+            .rule("comment", "#.*")
+            .rule("newline", "\\n")
+            .rule("space", "[ \t]+")
+            .rule("word", "[^#\\s]+")
+            .build()
+            .unwrap();
+        let tokens = lexer.tokenize("héllo # rest\nnext").unwrap();
+        let texts: Vec<(&str, &str)> = tokens.iter().map(|t| (t.kind, t.text)).collect();
+        assert_eq!(
+            texts,
+            vec![
+                ("word", "héllo"),
+                ("space", " "),
+                ("comment", "# rest"),
+                ("newline", "\n"),
+                ("word", "next"),
+            ]
+        );
+        assert_eq!(tokens[4].span.line, 2);
+    }
+
+    #[test]
+    fn test_pattern_errors() {
+        let err = Lexer::builder()
+            .rule(0, "ab")
+            .rule(1, "a(b")
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            LexError::Pattern {
+                rule: 1,
+                position: 3,
+                message: "expected ')'".to_string()
+            }
+        );
+        let err = Lexer::builder().rule(0, "*a").build().unwrap_err();
+        assert_eq!(
+            err,
+            LexError::Pattern {
+                rule: 0,
+                position: 0,
+                message: "nothing to repeat".to_string()
+            }
+        );
+    }
+}
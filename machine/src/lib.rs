@@ -1,2 +1,14 @@
+pub mod cellular;
 pub mod dfa;
+mod export;
+pub mod learning;
+pub mod lexer;
+#[macro_use]
+mod macros;
+pub mod mealy;
+pub mod moore;
 pub mod nfa;
+pub mod pda;
+pub mod random;
+pub mod regex;
+pub mod turing;
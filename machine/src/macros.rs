@@ -0,0 +1,259 @@
+/// Build a Dfa from a declarative description.
+/// States and alphabets are named by identifiers and numbered in the order
+/// they are declared, so the first state is 0 and the first alphabet is 0.
+///
+/// ```
+/// use machine::dfa;
+///
+/// // inputs with an even number of one.
+/// let dfa = dfa! {
+///     states: [even, odd],
+///     alphabets: [zero, one],
+///     initial: even,
+///     accept: [even],
+///     transitions: {
+///         even --zero--> even,
+///         even --one--> odd,
+///         odd --zero--> odd,
+///         odd --one--> even,
+///     }
+/// };
+/// assert!(dfa.is_language(vec![1, 0, 1]));
+/// assert!(!dfa.is_language(vec![1]));
+/// ```
+///
+/// Transitions are checked at compile time: they are expanded into an exhaustive
+/// match, so using an undeclared state or alphabet, forgetting a transition or
+/// giving two transitions for the same state and alphabet does not compile.
+///
+/// ```compile_fail
+/// use machine::dfa;
+///
+/// // q2 is not declared.
+/// let dfa = dfa! {
+///     states: [q0, q1],
+///     alphabets: [a],
+///     initial: q0,
+///     accept: [q1],
+///     transitions: { q0 --a--> q1, q1 --a--> q2 }
+/// };
+/// ```
+///
+/// ```compile_fail
+/// use machine::dfa;
+///
+/// // no transition from q1 on a.
+/// let dfa = dfa! {
+///     states: [q0, q1],
+///     alphabets: [a],
+///     initial: q0,
+///     accept: [q1],
+///     transitions: { q0 --a--> q1 }
+/// };
+/// ```
+///
+/// ```compile_fail
+/// use machine::dfa;
+///
+/// // two transitions from q0 on a.
+/// let dfa = dfa! {
+///     states: [q0, q1],
+///     alphabets: [a],
+///     initial: q0,
+///     accept: [q1],
+///     transitions: { q0 --a--> q1, q0 --a--> q0, q1 --a--> q1 }
+/// };
+/// ```
+#[macro_export]
+macro_rules! dfa {
+    (
+        states: [$($state:ident),+ $(,)?],
+        alphabets: [$($alphabet:ident),+ $(,)?],
+        initial: $initial:ident,
+        accept: [$($accept:ident),* $(,)?],
+        transitions: { $($from:ident --$on:ident--> $to:ident),* $(,)? } $(,)?
+    ) => {{
+        #[allow(non_camel_case_types)]
+        #[derive(Clone, Copy)]
+        enum State {
+            $($state),+
+        }
+
+        #[allow(non_camel_case_types)]
+        #[derive(Clone, Copy)]
+        enum Alphabet {
+            $($alphabet),+
+        }
+
+        // fails to compile when the same state and alphabet appear twice.
+        const TRANSITIONS: &[(usize, usize)] = &[$((State::$from as usize, Alphabet::$on as usize)),*];
+        const _: () = {
+            let mut i = 0;
+            while i < TRANSITIONS.len() {
+                let mut j = i + 1;
+                while j < TRANSITIONS.len() {
+                    if TRANSITIONS[i].0 == TRANSITIONS[j].0 && TRANSITIONS[i].1 == TRANSITIONS[j].1 {
+                        panic!("dfa! has two transitions from a state on the same alphabet");
+                    }
+                    j += 1;
+                }
+                i += 1;
+            }
+        };
+
+        fn next(transition: (State, Alphabet)) -> State {
+            match transition {
+                $((State::$from, Alphabet::$on) => State::$to,)*
+            }
+        }
+
+        let states = [$(State::$state),+];
+        let alphabets = [$(Alphabet::$alphabet),+];
+        // bindings of type State or Alphabet would clash with variants named like them.
+        let transition_function = (0..states.len())
+            .map(|i| {
+                (0..alphabets.len())
+                    .map(|j| next((states[i], alphabets[j])) as u64)
+                    .collect()
+            })
+            .collect();
+        $crate::dfa::Dfa::new(
+            states.len() as u64,
+            alphabets.len() as u64,
+            transition_function,
+            State::$initial as u64,
+            vec![$(State::$accept as u64),*],
+        )
+        .expect("dfa! always describes a valid Dfa")
+    }};
+}
+
+/// Build an Nfa from a declarative description, like dfa!.
+/// Any number of initial states and transitions per state and alphabet can be given,
+/// and epsilon transitions are written as `from --> to` in an optional epsilon block.
+/// Using an undeclared state or alphabet does not compile.
+///
+/// ```
+/// use machine::nfa;
+///
+/// // inputs whose second symbol from the end is one.
+/// let nfa = nfa! {
+///     states: [start, guess, last, done],
+///     alphabets: [zero, one],
+///     initial: [start],
+///     accept: [done],
+///     transitions: {
+///         start --zero--> start,
+///         start --one--> start,
+///         start --one--> guess,
+///         guess --zero--> last,
+///         guess --one--> last,
+///     },
+///     epsilon: {
+///         last --> done,
+///     }
+/// };
+/// assert!(nfa.is_language(vec![0, 1, 0]));
+/// assert!(!nfa.is_language(vec![]));
+/// assert!(!nfa.is_language(vec![1, 0, 0]));
+/// ```
+///
+/// ```compile_fail
+/// use machine::nfa;
+///
+/// // q2 is not declared.
+/// let nfa = nfa! {
+///     states: [q0, q1],
+///     alphabets: [a],
+///     initial: [q0],
+///     accept: [q1],
+///     transitions: { q0 --a--> q2 }
+/// };
+/// ```
+#[macro_export]
+macro_rules! nfa {
+    (
+        states: [$($state:ident),+ $(,)?],
+        alphabets: [$($alphabet:ident),+ $(,)?],
+        initial: [$($initial:ident),* $(,)?],
+        accept: [$($accept:ident),* $(,)?],
+        transitions: { $($from:ident --$on:ident--> $to:ident),* $(,)? }
+        $(, epsilon: { $($epsilon_from:ident --> $epsilon_to:ident),* $(,)? })? $(,)?
+    ) => {{
+        #[allow(non_camel_case_types)]
+        #[derive(Clone, Copy)]
+        enum State {
+            $($state),+
+        }
+
+        #[allow(non_camel_case_types)]
+        #[derive(Clone, Copy)]
+        enum Alphabet {
+            $($alphabet),+
+        }
+
+        let states = [$(State::$state),+];
+        let alphabets = [$(Alphabet::$alphabet),+];
+        #[allow(unused_mut)]
+        let mut transition_function = vec![vec![Vec::new(); alphabets.len()]; states.len()];
+        $(transition_function[State::$from as usize][Alphabet::$on as usize].push(State::$to as u64);)*
+        #[allow(unused_mut)]
+        let mut epsilon_transitions = vec![Vec::new(); states.len()];
+        $($(epsilon_transitions[State::$epsilon_from as usize].push(State::$epsilon_to as u64);)*)?
+        $crate::nfa::Nfa::new(
+            states.len() as u64,
+            alphabets.len() as u64,
+            transition_function,
+            epsilon_transitions,
+            vec![$(State::$initial as u64),*],
+            vec![$(State::$accept as u64),*],
+        )
+        .expect("nfa! always describes a valid Nfa")
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_dfa_macro() {
+        // inputs over {a, b} ending with a b.
+        let dfa = dfa! {
+            states: [start, seen_a, seen_ab],
+            alphabets: [a, b],
+            initial: start,
+            accept: [seen_ab],
+            transitions: {
+                start --a--> seen_a,
+                start --b--> start,
+                seen_a --a--> seen_a,
+                seen_a --b--> seen_ab,
+                seen_ab --a--> seen_a,
+                seen_ab --b--> start,
+            }
+        };
+        assert_eq!(dfa.states(), 3);
+        assert_eq!(dfa.alphabets(), 2);
+        assert_eq!(dfa.next_state(1, 1), Some(2));
+        assert!(dfa.is_language(vec![1, 0, 1]));
+        assert!(!dfa.is_language(vec![0, 1, 1]));
+    }
+
+    #[test]
+    fn test_nfa_macro() {
+        let nfa = nfa! {
+            states: [p, q],
+            alphabets: [a],
+            initial: [p, q],
+            accept: [q],
+            transitions: {
+                p --a--> q,
+                p --a--> q,
+            }
+        };
+        assert_eq!(nfa.states(), 2);
+        assert_eq!(nfa.next_states(0, 0), &[1]);
+        assert!(nfa.is_language(vec![]));
+        assert!(nfa.is_language(vec![0]));
+        assert!(!nfa.is_language(vec![0, 0]));
+    }
+}
@@ -0,0 +1,184 @@
+use crate::dfa::{check_shape, validate_table, DfaError};
+use crate::moore::Moore;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Mealy machine, a finite state machine producing an output on every transition.
+/// States and alphabets are u64 integers as in Dfa.
+/// output_function[q][a] is the output produced when moving from q on alphabet a.
+#[derive(Debug, Clone)]
+pub struct Mealy<O> {
+    states: u64,
+    alphabets: u64,
+    transition_function: Vec<Vec<u64>>,
+    output_function: Vec<Vec<O>>,
+    initial_state: u64,
+}
+
+impl<O: Clone> Mealy<O> {
+    /// Build a Mealy machine with the same validation as Dfa::new.
+    /// output_function must have the same shape as transition_function.
+    pub fn new(
+        states: u64,
+        alphabets: u64,
+        transition_function: Vec<Vec<u64>>,
+        output_function: Vec<Vec<O>>,
+        initial_state: u64,
+    ) -> Result<Self, DfaError> {
+        validate_table(states, alphabets, &transition_function, initial_state)?;
+        check_shape(states, alphabets, &output_function).map_err(|error| match error {
+            DfaError::WrongRowCount { expected, found } => {
+                DfaError::WrongOutputCount { expected, found }
+            }
+            DfaError::WrongColumnCount {
+                state,
+                expected,
+                found,
+            } => DfaError::WrongOutputColumnCount {
+                state,
+                expected,
+                found,
+            },
+            error => error,
+        })?;
+        Ok(Mealy {
+            states,
+            alphabets,
+            transition_function,
+            output_function,
+            initial_state,
+        })
+    }
+
+    pub fn states(&self) -> u64 {
+        self.states
+    }
+
+    pub fn alphabets(&self) -> u64 {
+        self.alphabets
+    }
+
+    /// Output produced on every transition, one for each alphabet of input.
+    pub fn execute(&self, input: Vec<u64>) -> Result<Vec<O>, DfaError> {
+        if let Some(position) = input.iter().position(|a| a >= &self.alphabets) {
+            return Err(DfaError::InvalidInput {
+                position,
+                alphabet: input[position],
+            });
+        }
+
+        let mut state = self.initial_state as usize;
+        let mut output = Vec::with_capacity(input.len());
+        for a in input {
+            output.push(self.output_function[state][a as usize].clone());
+            state = self.transition_function[state][a as usize] as usize;
+        }
+        Ok(output)
+    }
+}
+
+impl<O: Clone + Eq + Hash> Mealy<O> {
+    /// Equivalent Moore machine.
+    /// Each state of the Moore machine is a pair of a Mealy state and the output
+    /// of a transition into it, so the Moore output of a state is the Mealy output
+    /// of the transition just taken. The initial state outputs initial_output,
+    /// which is the extra first output of Moore::execute.
+    pub fn to_moore(&self, initial_output: O) -> Moore<O> {
+        let initial = (self.initial_state, initial_output);
+        let mut ids: HashMap<(u64, O), u64> = HashMap::new();
+        let mut pairs = vec![initial.clone()];
+        ids.insert(initial, 0);
+
+        let mut transition_function = Vec::new();
+        let mut i = 0;
+        while i < pairs.len() {
+            let q = pairs[i].0 as usize;
+            let row = (0..self.alphabets as usize)
+                .map(|a| {
+                    let next = (
+                        self.transition_function[q][a],
+                        self.output_function[q][a].clone(),
+                    );
+                    *ids.entry(next.clone()).or_insert_with(|| {
+                        pairs.push(next);
+                        pairs.len() as u64 - 1
+                    })
+                })
+                .collect();
+            transition_function.push(row);
+            i += 1;
+        }
+
+        let output_function = pairs.into_iter().map(|(_, o)| o).collect();
+        Moore::new(
+            transition_function.len() as u64,
+            self.alphabets,
+            transition_function,
+            output_function,
+            0,
+        )
+        .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // outputs 1 when the alphabet differs from the previous one.
+    fn edge_detector() -> Mealy<u8> {
+        Mealy::new(
+            2,
+            2,
+            vec![vec![0, 1], vec![0, 1]],
+            vec![vec![0, 1], vec![1, 0]],
+            0,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_execute() {
+        let mealy = edge_detector();
+        assert_eq!(mealy.execute(vec![0, 1, 1, 0, 0]), Ok(vec![0, 1, 0, 1, 0]));
+        assert_eq!(mealy.execute(vec![]), Ok(vec![]));
+        assert_eq!(
+            mealy.execute(vec![0, 2]),
+            Err(DfaError::InvalidInput {
+                position: 1,
+                alphabet: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_new_wrong_output_shape() {
+        let res = Mealy::new(1, 2, vec![vec![0, 0]], vec![vec!['a']], 0);
+        assert_eq!(
+            res.unwrap_err(),
+            DfaError::WrongOutputColumnCount {
+                state: 0,
+                expected: 2,
+                found: 1
+            }
+        );
+        let res = Mealy::new(1, 2, vec![vec![0, 0]], Vec::<Vec<char>>::new(), 0);
+        assert_eq!(
+            res.unwrap_err(),
+            DfaError::WrongOutputCount {
+                expected: 1,
+                found: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_to_moore() {
+        let mealy = edge_detector();
+        let moore = mealy.to_moore(0);
+        let input = vec![1, 1, 0, 1, 0, 0];
+        let output = moore.execute(input.clone()).unwrap();
+        assert_eq!(output[0], 0);
+        assert_eq!(output[1..].to_vec(), mealy.execute(input).unwrap());
+    }
+}
@@ -0,0 +1,140 @@
+use crate::dfa::{validate_table, DfaError};
+use crate::mealy::Mealy;
+
+/// Moore machine, a finite state machine whose output depends only on the current state.
+/// States and alphabets are u64 integers as in Dfa.
+/// output_function[q] is the output of state q.
+#[derive(Debug, Clone)]
+pub struct Moore<O> {
+    states: u64,
+    alphabets: u64,
+    transition_function: Vec<Vec<u64>>,
+    output_function: Vec<O>,
+    initial_state: u64,
+}
+
+impl<O: Clone> Moore<O> {
+    /// Build a Moore machine with the same validation as Dfa::new.
+    /// output_function must have one output per state.
+    pub fn new(
+        states: u64,
+        alphabets: u64,
+        transition_function: Vec<Vec<u64>>,
+        output_function: Vec<O>,
+        initial_state: u64,
+    ) -> Result<Self, DfaError> {
+        validate_table(states, alphabets, &transition_function, initial_state)?;
+        if output_function.len() != states as usize {
+            return Err(DfaError::WrongOutputCount {
+                expected: states,
+                found: output_function.len(),
+            });
+        }
+        Ok(Moore {
+            states,
+            alphabets,
+            transition_function,
+            output_function,
+            initial_state,
+        })
+    }
+
+    pub fn states(&self) -> u64 {
+        self.states
+    }
+
+    pub fn alphabets(&self) -> u64 {
+        self.alphabets
+    }
+
+    /// Output of every visited state, starting with the initial state,
+    /// so the output is one longer than input.
+    pub fn execute(&self, input: Vec<u64>) -> Result<Vec<O>, DfaError> {
+        if let Some(position) = input.iter().position(|a| a >= &self.alphabets) {
+            return Err(DfaError::InvalidInput {
+                position,
+                alphabet: input[position],
+            });
+        }
+
+        let mut state = self.initial_state as usize;
+        let mut output = Vec::with_capacity(input.len() + 1);
+        output.push(self.output_function[state].clone());
+        for a in input {
+            state = self.transition_function[state][a as usize] as usize;
+            output.push(self.output_function[state].clone());
+        }
+        Ok(output)
+    }
+
+    /// Equivalent Mealy machine, whose output on a transition is the output
+    /// of the state it goes to. Its output is the output of Moore::execute
+    /// without the first one.
+    pub fn to_mealy(&self) -> Mealy<O> {
+        let output_function = self
+            .transition_function
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&next| self.output_function[next as usize].clone())
+                    .collect()
+            })
+            .collect();
+        Mealy::new(
+            self.states,
+            self.alphabets,
+            self.transition_function.clone(),
+            output_function,
+            self.initial_state,
+        )
+        .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // outputs the number of 1 seen so far modulo 3.
+    fn counter() -> Moore<u64> {
+        Moore::new(
+            3,
+            2,
+            vec![vec![0, 1], vec![1, 2], vec![2, 0]],
+            vec![0, 1, 2],
+            0,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_execute() {
+        let moore = counter();
+        assert_eq!(moore.execute(vec![1, 0, 1, 1]), Ok(vec![0, 1, 1, 2, 0]));
+        assert_eq!(moore.execute(vec![]), Ok(vec![0]));
+    }
+
+    #[test]
+    fn test_new_wrong_output_count() {
+        let res = Moore::new(2, 1, vec![vec![1], vec![0]], vec!["a"], 0);
+        assert_eq!(
+            res.unwrap_err(),
+            DfaError::WrongOutputCount {
+                expected: 2,
+                found: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let moore = counter();
+        let mealy = moore.to_mealy();
+        let input = vec![1, 1, 0, 1, 0, 1, 1];
+        let output = moore.execute(input.clone()).unwrap();
+        assert_eq!(output[1..].to_vec(), mealy.execute(input.clone()).unwrap());
+
+        let back = mealy.to_moore(0);
+        assert_eq!(back.execute(input).unwrap(), output);
+    }
+}
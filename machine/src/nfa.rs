@@ -1,13 +1,310 @@
-struct Nfa {}
+use crate::dfa::{check_shape, Dfa, DfaError};
+use std::collections::HashSet;
 
-impl Nfa {}
+/// Nondeterministic Finite Automaton
+/// States and alphabets are u64 integers as in Dfa.
+/// transition_function[q][a] is the set of states q can move to on alphabet a,
+/// and epsilon_transitions[q] the set of states q can move to without reading input.
+/// Any number of states, including none, can be initial states.
+#[derive(Debug, Clone)]
+pub struct Nfa {
+    states: u64,
+    alphabets: u64,
+    transition_function: Vec<Vec<Vec<u64>>>,
+    epsilon_transitions: Vec<Vec<u64>>,
+    initial_states: HashSet<u64>,
+    accept_states: HashSet<u64>,
+}
+
+impl Nfa {
+    /// Build an Nfa with the same validation as Dfa::new.
+    /// Targets of each transition are stored sorted without duplicates.
+    pub fn new(
+        states: u64,
+        alphabets: u64,
+        transition_function: Vec<Vec<Vec<u64>>>,
+        epsilon_transitions: Vec<Vec<u64>>,
+        initial_states: Vec<u64>,
+        accept_states: Vec<u64>,
+    ) -> Result<Self, DfaError> {
+        check_shape(states, alphabets, &transition_function)?;
+        if epsilon_transitions.len() != states as usize {
+            return Err(DfaError::WrongRowCount {
+                expected: states,
+                found: epsilon_transitions.len(),
+            });
+        }
+        for (state, row) in transition_function.iter().enumerate() {
+            for (alphabet, targets) in row.iter().enumerate() {
+                if let Some(&target) = targets.iter().find(|&&q| q >= states) {
+                    return Err(DfaError::TargetOutOfRange {
+                        state: state as u64,
+                        alphabet: alphabet as u64,
+                        target,
+                    });
+                }
+            }
+        }
+        if let Some(&target) = epsilon_transitions.iter().flatten().find(|&&q| q >= states) {
+            return Err(DfaError::StateOutOfRange(target));
+        }
+        if let Some(&q) = initial_states.iter().find(|&&q| q >= states) {
+            return Err(DfaError::InitialStateOutOfRange(q));
+        }
+        if let Some(&q) = accept_states.iter().find(|&&q| q >= states) {
+            return Err(DfaError::AcceptStateOutOfRange(q));
+        }
+
+        let normalize = |mut targets: Vec<u64>| {
+            targets.sort_unstable();
+            targets.dedup();
+            targets
+        };
+        Ok(Nfa {
+            states,
+            alphabets,
+            transition_function: transition_function
+                .into_iter()
+                .map(|row| row.into_iter().map(normalize).collect())
+                .collect(),
+            epsilon_transitions: epsilon_transitions.into_iter().map(normalize).collect(),
+            initial_states: initial_states.into_iter().collect(),
+            accept_states: accept_states.into_iter().collect(),
+        })
+    }
+
+    pub fn states(&self) -> u64 {
+        self.states
+    }
+
+    pub fn alphabets(&self) -> u64 {
+        self.alphabets
+    }
+
+    pub fn is_initial_state(&self, state: u64) -> bool {
+        self.initial_states.contains(&state)
+    }
+
+    pub fn is_accept_state(&self, state: u64) -> bool {
+        self.accept_states.contains(&state)
+    }
+
+    /// States reachable from state on alphabet, empty if either is out of range.
+    pub fn next_states(&self, state: u64, alphabet: u64) -> &[u64] {
+        self.transition_function
+            .get(state as usize)
+            .and_then(|row| row.get(alphabet as usize))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// States reachable from state by one epsilon transition.
+    pub fn epsilon_states(&self, state: u64) -> &[u64] {
+        self.epsilon_transitions
+            .get(state as usize)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Add every state reachable by epsilon transitions to the active set.
+    fn close(&self, active: &mut [bool]) {
+        let mut stack: Vec<u64> = (0..self.states).filter(|&q| active[q as usize]).collect();
+        while let Some(q) = stack.pop() {
+            for &next in &self.epsilon_transitions[q as usize] {
+                if !active[next as usize] {
+                    active[next as usize] = true;
+                    stack.push(next);
+                }
+            }
+        }
+    }
+
+    /// Epsilon closure of states, sorted.
+    pub fn epsilon_closure(&self, states: &[u64]) -> Vec<u64> {
+        let mut active = vec![false; self.states as usize];
+        for &q in states.iter().filter(|&&q| q < self.states) {
+            active[q as usize] = true;
+        }
+        self.close(&mut active);
+        (0..self.states).filter(|&q| active[q as usize]).collect()
+    }
+
+    /// Whether input is accepted, tracking the set of active states:
+    /// the epsilon closure of initial states, then the epsilon closure of every
+    /// state reachable from an active state on each alphabet.
+    pub fn execute(&self, input: Vec<u64>) -> Result<bool, DfaError> {
+        if let Some(position) = input.iter().position(|&a| a >= self.alphabets) {
+            return Err(DfaError::InvalidInput {
+                position,
+                alphabet: input[position],
+            });
+        }
+
+        let n = self.states as usize;
+        let mut active = vec![false; n];
+        for &q in &self.initial_states {
+            active[q as usize] = true;
+        }
+        self.close(&mut active);
+        for a in input {
+            let mut next = vec![false; n];
+            for q in (0..n).filter(|&q| active[q]) {
+                for &r in &self.transition_function[q][a as usize] {
+                    next[r as usize] = true;
+                }
+            }
+            self.close(&mut next);
+            active = next;
+        }
+        Ok(self.accept_states.iter().any(|&q| active[q as usize]))
+    }
+
+    pub fn is_language(&self, input: Vec<u64>) -> bool {
+        self.execute(input).unwrap_or(false)
+    }
+}
+
+impl From<Dfa> for Nfa {
+    fn from(dfa: Dfa) -> Self {
+        let transition_function = (0..dfa.states())
+            .map(|q| {
+                (0..dfa.alphabets())
+                    .map(|a| vec![dfa.next_state(q, a).unwrap()])
+                    .collect()
+            })
+            .collect();
+        Nfa {
+            states: dfa.states(),
+            alphabets: dfa.alphabets(),
+            transition_function,
+            epsilon_transitions: vec![Vec::new(); dfa.states() as usize],
+            initial_states: std::iter::once(dfa.initial_state()).collect(),
+            accept_states: (0..dfa.states())
+                .filter(|&q| dfa.is_accept_state(q))
+                .collect(),
+        }
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::random::{random_word, DfaGenerator, Rng};
+
+    // inputs over {0, 1} whose second symbol from the end is 1.
+    fn second_from_end() -> Nfa {
+        Nfa::new(
+            3,
+            2,
+            vec![
+                vec![vec![0], vec![0, 1]],
+                vec![vec![2], vec![2]],
+                vec![vec![], vec![]],
+            ],
+            vec![vec![], vec![], vec![]],
+            vec![0],
+            vec![2],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_execute() {
+        let nfa = second_from_end();
+        assert_eq!(nfa.execute(vec![1, 0]), Ok(true));
+        assert_eq!(nfa.execute(vec![0, 0, 1, 1]), Ok(true));
+        assert_eq!(nfa.execute(vec![1, 0, 0]), Ok(false));
+        assert_eq!(nfa.execute(vec![]), Ok(false));
+        assert_eq!(
+            nfa.execute(vec![0, 2]),
+            Err(DfaError::InvalidInput {
+                position: 1,
+                alphabet: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_epsilon_and_initial_states() {
+        // 0* from state 0, or 1* from state 2, joined by epsilon from 1 to 2.
+        let nfa = Nfa::new(
+            3,
+            2,
+            vec![
+                vec![vec![0, 1], vec![]],
+                vec![vec![], vec![]],
+                vec![vec![], vec![2]],
+            ],
+            vec![vec![], vec![2], vec![]],
+            vec![0, 2],
+            vec![0, 2],
+        )
+        .unwrap();
+        assert_eq!(nfa.epsilon_closure(&[1]), vec![1, 2]);
+        assert!(nfa.is_language(vec![]));
+        assert!(nfa.is_language(vec![1, 1]));
+        assert!(nfa.is_language(vec![0, 0, 1]));
+        assert!(!nfa.is_language(vec![1, 0]));
+
+        let none = Nfa::new(1, 1, vec![vec![vec![0]]], vec![vec![]], vec![], vec![0]).unwrap();
+        assert!(!none.is_language(vec![]));
+    }
+
+    #[test]
+    fn test_epsilon_cycle() {
+        let nfa = Nfa::new(
+            2,
+            1,
+            vec![vec![vec![]], vec![vec![0]]],
+            vec![vec![1], vec![0]],
+            vec![0],
+            vec![1],
+        )
+        .unwrap();
+        assert_eq!(nfa.epsilon_closure(&[0]), vec![0, 1]);
+        assert!(nfa.is_language(vec![0, 0, 0]));
+    }
+
+    #[test]
+    fn test_new_invalid() {
+        let row = || vec![vec![vec![0]]];
+        assert_eq!(
+            Nfa::new(1, 1, vec![vec![vec![1]]], vec![vec![]], vec![0], vec![]).unwrap_err(),
+            DfaError::TargetOutOfRange {
+                state: 0,
+                alphabet: 0,
+                target: 1
+            }
+        );
+        assert_eq!(
+            Nfa::new(1, 1, row(), vec![], vec![0], vec![]).unwrap_err(),
+            DfaError::WrongRowCount {
+                expected: 1,
+                found: 0
+            }
+        );
+        assert_eq!(
+            Nfa::new(1, 1, row(), vec![vec![3]], vec![0], vec![]).unwrap_err(),
+            DfaError::StateOutOfRange(3)
+        );
+        assert_eq!(
+            Nfa::new(1, 1, row(), vec![vec![]], vec![0, 1], vec![]).unwrap_err(),
+            DfaError::InitialStateOutOfRange(1)
+        );
+        assert_eq!(
+            Nfa::new(1, 1, row(), vec![vec![]], vec![0], vec![2]).unwrap_err(),
+            DfaError::AcceptStateOutOfRange(2)
+        );
+    }
 
     #[test]
-    fn test() {
-        assert_eq!(4, 2 + 2);
+    fn test_from_dfa() {
+        let mut rng = Rng::new(25);
+        for _ in 0..20 {
+            let dfa = DfaGenerator::new(6, 3).generate(&mut rng);
+            let nfa = Nfa::from(dfa.clone());
+            for _ in 0..20 {
+                let word = random_word(&mut rng, 3, 10);
+                assert_eq!(nfa.execute(word.clone()), dfa.execute(word));
+            }
+        }
     }
 }
@@ -0,0 +1,367 @@
+use std::collections::{HashSet, VecDeque};
+use std::error::Error;
+use std::fmt;
+
+/// Errors returned while building or executing a Pda.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PdaError {
+    /// a transition starts from or goes to a state which does not exist.
+    StateOutOfRange(u64),
+    /// a transition reads an alphabet which does not exist.
+    AlphabetOutOfRange(u64),
+    /// a transition or the initial stack uses a stack symbol which does not exist.
+    StackSymbolOutOfRange(u64),
+    /// initial state is not a valid state.
+    InitialStateOutOfRange(u64),
+    /// one of accept states is not a valid state.
+    AcceptStateOutOfRange(u64),
+    /// input contains an alphabet out of range at position.
+    InvalidInput { position: usize, alphabet: u64 },
+}
+
+impl fmt::Display for PdaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PdaError::StateOutOfRange(state) => write!(f, "state {} is out of range", state),
+            PdaError::AlphabetOutOfRange(alphabet) => {
+                write!(f, "alphabet {} is out of range", alphabet)
+            }
+            PdaError::StackSymbolOutOfRange(symbol) => {
+                write!(f, "stack symbol {} is out of range", symbol)
+            }
+            PdaError::InitialStateOutOfRange(state) => {
+                write!(f, "initial state {} is out of range", state)
+            }
+            PdaError::AcceptStateOutOfRange(state) => {
+                write!(f, "accept state {} is out of range", state)
+            }
+            PdaError::InvalidInput { position, alphabet } => write!(
+                f,
+                "invalid alphabet {} at position {} of input",
+                alphabet, position
+            ),
+        }
+    }
+}
+
+impl Error for PdaError {}
+
+/// Transition of a Pda.
+/// From state from, reading input (None for an epsilon transition)
+/// with pop on top of the stack, the Pda goes to state to and replaces pop
+/// with push. push[0] becomes the new top, so an empty push just pops.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transition {
+    pub from: u64,
+    pub input: Option<u64>,
+    pub pop: u64,
+    pub to: u64,
+    pub push: Vec<u64>,
+}
+
+impl Transition {
+    pub fn new(from: u64, input: Option<u64>, pop: u64, to: u64, push: Vec<u64>) -> Self {
+        Transition {
+            from,
+            input,
+            pop,
+            to,
+            push,
+        }
+    }
+}
+
+/// How a Pda accepts an input once it is fully read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Acceptance {
+    /// the Pda is in one of accept states.
+    FinalState,
+    /// the stack is empty.
+    EmptyStack,
+}
+
+/// Result of exploring the configurations of a Pda.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Accepted,
+    /// every reachable configuration was explored without accepting.
+    Rejected,
+    /// the bound on configurations was reached before a decision.
+    Undecided,
+}
+
+/// Nondeterministic pushdown automaton.
+/// States, alphabets and stack symbols are u64 integers as in Dfa,
+/// and the stack starts with the single symbol initial_stack.
+#[derive(Debug, Clone)]
+pub struct Pda {
+    states: u64,
+    alphabets: u64,
+    stack_alphabets: u64,
+    transitions: Vec<Transition>,
+    // indices of transitions starting from each state.
+    outgoing: Vec<Vec<usize>>,
+    initial_state: u64,
+    initial_stack: u64,
+    accept_states: HashSet<u64>,
+}
+
+/// State, number of alphabets read and stack with its top at the end.
+type Configuration = (u64, usize, Vec<u64>);
+
+impl Pda {
+    pub fn new(
+        states: u64,
+        alphabets: u64,
+        stack_alphabets: u64,
+        transitions: Vec<Transition>,
+        initial_state: u64,
+        initial_stack: u64,
+        accept_states: Vec<u64>,
+    ) -> Result<Self, PdaError> {
+        if initial_state >= states {
+            return Err(PdaError::InitialStateOutOfRange(initial_state));
+        }
+        if initial_stack >= stack_alphabets {
+            return Err(PdaError::StackSymbolOutOfRange(initial_stack));
+        }
+        if let Some(&q) = accept_states.iter().find(|&&q| q >= states) {
+            return Err(PdaError::AcceptStateOutOfRange(q));
+        }
+
+        let mut outgoing = vec![Vec::new(); states as usize];
+        for (i, t) in transitions.iter().enumerate() {
+            if t.from >= states {
+                return Err(PdaError::StateOutOfRange(t.from));
+            }
+            if t.to >= states {
+                return Err(PdaError::StateOutOfRange(t.to));
+            }
+            if let Some(a) = t.input.filter(|&a| a >= alphabets) {
+                return Err(PdaError::AlphabetOutOfRange(a));
+            }
+            if let Some(&s) = std::iter::once(&t.pop)
+                .chain(&t.push)
+                .find(|&&s| s >= stack_alphabets)
+            {
+                return Err(PdaError::StackSymbolOutOfRange(s));
+            }
+            outgoing[t.from as usize].push(i);
+        }
+
+        Ok(Pda {
+            states,
+            alphabets,
+            stack_alphabets,
+            transitions,
+            outgoing,
+            initial_state,
+            initial_stack,
+            accept_states: accept_states.into_iter().collect(),
+        })
+    }
+
+    pub fn states(&self) -> u64 {
+        self.states
+    }
+
+    pub fn alphabets(&self) -> u64 {
+        self.alphabets
+    }
+
+    pub fn stack_alphabets(&self) -> u64 {
+        self.stack_alphabets
+    }
+
+    pub fn transitions(&self) -> &[Transition] {
+        &self.transitions
+    }
+
+    /// Breadth first search over configurations, visiting at most bound distinct
+    /// configurations. Epsilon transitions pushing more than they pop can make
+    /// the search infinite, which is reported as Outcome::Undecided.
+    pub fn execute(
+        &self,
+        input: &[u64],
+        acceptance: Acceptance,
+        bound: usize,
+    ) -> Result<Outcome, PdaError> {
+        if let Some(position) = input.iter().position(|&a| a >= self.alphabets) {
+            return Err(PdaError::InvalidInput {
+                position,
+                alphabet: input[position],
+            });
+        }
+
+        let accepts = |(state, position, stack): &Configuration| {
+            *position == input.len()
+                && match acceptance {
+                    Acceptance::FinalState => self.accept_states.contains(state),
+                    Acceptance::EmptyStack => stack.is_empty(),
+                }
+        };
+        let initial: Configuration = (self.initial_state, 0, vec![self.initial_stack]);
+        if accepts(&initial) {
+            return Ok(Outcome::Accepted);
+        }
+        let mut visited: HashSet<Configuration> = HashSet::new();
+        let mut queue = VecDeque::new();
+        visited.insert(initial.clone());
+        queue.push_back(initial);
+
+        // acceptance is checked when a configuration is found, so reaching the bound
+        // never hides an accepting configuration waiting in the queue.
+        while let Some((state, position, stack)) = queue.pop_front() {
+            let top = match stack.last() {
+                Some(&top) => top,
+                None => continue,
+            };
+
+            for &i in &self.outgoing[state as usize] {
+                let t = &self.transitions[i];
+                if t.pop != top {
+                    continue;
+                }
+                let next_position = match t.input {
+                    None => position,
+                    Some(a) if input.get(position) == Some(&a) => position + 1,
+                    Some(_) => continue,
+                };
+                let mut next_stack = stack[..stack.len() - 1].to_vec();
+                next_stack.extend(t.push.iter().rev());
+                let next = (t.to, next_position, next_stack);
+                if visited.contains(&next) {
+                    continue;
+                }
+                if accepts(&next) {
+                    return Ok(Outcome::Accepted);
+                }
+                if visited.len() >= bound {
+                    return Ok(Outcome::Undecided);
+                }
+                visited.insert(next.clone());
+                queue.push_back(next);
+            }
+        }
+        Ok(Outcome::Rejected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // balanced brackets, 0 is ( and 1 is ), stack symbols are 0 bottom and 1 open.
+    fn brackets() -> Pda {
+        Pda::new(
+            2,
+            2,
+            2,
+            vec![
+                Transition::new(0, Some(0), 0, 0, vec![1, 0]),
+                Transition::new(0, Some(0), 1, 0, vec![1, 1]),
+                Transition::new(0, Some(1), 1, 0, vec![]),
+                Transition::new(0, None, 0, 1, vec![]),
+            ],
+            0,
+            0,
+            vec![1],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_balanced_brackets() {
+        let pda = brackets();
+        for &acceptance in [Acceptance::FinalState, Acceptance::EmptyStack].iter() {
+            let run = |input: &[u64]| pda.execute(input, acceptance, 1000).unwrap();
+            assert_eq!(run(&[]), Outcome::Accepted);
+            assert_eq!(run(&[0, 0, 1, 0, 1, 1]), Outcome::Accepted);
+            assert_eq!(run(&[0, 1, 1, 0]), Outcome::Rejected);
+            assert_eq!(run(&[0, 0, 1]), Outcome::Rejected);
+        }
+    }
+
+    #[test]
+    fn test_even_palindromes() {
+        // guesses the middle of the input, stack symbols are 0 bottom, 1 and 2 for alphabets.
+        let mut transitions = vec![
+            Transition::new(0, None, 0, 1, vec![0]),
+            Transition::new(0, None, 1, 1, vec![1]),
+            Transition::new(0, None, 2, 1, vec![2]),
+            Transition::new(1, None, 0, 2, vec![0]),
+        ];
+        for a in 0..2 {
+            for s in 0..3 {
+                transitions.push(Transition::new(0, Some(a), s, 0, vec![a + 1, s]));
+            }
+            transitions.push(Transition::new(1, Some(a), a + 1, 1, vec![]));
+        }
+        let pda = Pda::new(3, 2, 3, transitions, 0, 0, vec![2]).unwrap();
+        let run = |input: &[u64]| pda.execute(input, Acceptance::FinalState, 1000).unwrap();
+        assert_eq!(run(&[0, 1, 1, 0]), Outcome::Accepted);
+        assert_eq!(run(&[1, 0, 0, 0, 0, 1]), Outcome::Accepted);
+        assert_eq!(run(&[0, 1, 0]), Outcome::Rejected);
+        assert_eq!(run(&[0, 1, 0, 1]), Outcome::Rejected);
+    }
+
+    #[test]
+    fn test_bound() {
+        // keeps pushing on epsilon transitions, so the search never ends.
+        let pda = Pda::new(
+            1,
+            1,
+            1,
+            vec![Transition::new(0, None, 0, 0, vec![0, 0])],
+            0,
+            0,
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(
+            pda.execute(&[0], Acceptance::FinalState, 100),
+            Ok(Outcome::Undecided)
+        );
+        assert_eq!(
+            pda.execute(&[1], Acceptance::FinalState, 100),
+            Err(PdaError::InvalidInput {
+                position: 0,
+                alphabet: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_accepting_at_bound() {
+        // the empty input is accepted right after the first epsilon transition.
+        let pda = brackets();
+        assert_eq!(
+            pda.execute(&[], Acceptance::FinalState, 1),
+            Ok(Outcome::Accepted)
+        );
+        assert_eq!(
+            pda.execute(&[0, 1], Acceptance::FinalState, 4),
+            Ok(Outcome::Accepted)
+        );
+    }
+
+    #[test]
+    fn test_new_invalid() {
+        let new = |transitions, initial_stack| {
+            Pda::new(2, 1, 2, transitions, 0, initial_stack, vec![1]).unwrap_err()
+        };
+        assert_eq!(new(vec![], 2), PdaError::StackSymbolOutOfRange(2));
+        assert_eq!(
+            new(vec![Transition::new(0, Some(0), 0, 2, vec![])], 0),
+            PdaError::StateOutOfRange(2)
+        );
+        assert_eq!(
+            new(vec![Transition::new(0, Some(1), 0, 1, vec![])], 0),
+            PdaError::AlphabetOutOfRange(1)
+        );
+        assert_eq!(
+            new(vec![Transition::new(0, None, 0, 1, vec![1, 3])], 0),
+            PdaError::StackSymbolOutOfRange(3)
+        );
+    }
+}
@@ -0,0 +1,364 @@
+use crate::dfa::Dfa;
+use crate::nfa::Nfa;
+
+/// Small seeded pseudo random generator (xorshift64*),
+/// so fuzzing and random testing are reproducible without extra dependencies.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // one splitmix64 step spreads nearby seeds over the whole state space.
+        // It is a bijection, so only one seed maps to 0, where xorshift gets stuck.
+        let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        Rng(if z == 0 { 0x9e37_79b9_7f4a_7c15 } else { z })
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Uniform number in [0, n). n must be positive.
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "below(0) has no result");
+        // Lemire's multiply and shift, rejecting the low products which would
+        // make some results more likely when n does not divide 2^64.
+        let mut m = self.next_u64() as u128 * n as u128;
+        if (m as u64) < n {
+            let threshold = n.wrapping_neg() % n;
+            while (m as u64) < threshold {
+                m = self.next_u64() as u128 * n as u128;
+            }
+        }
+        (m >> 64) as u64
+    }
+
+    /// true with probability p.
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+}
+
+/// Generator of random Dfas.
+///
+/// ```
+/// use machine::random::{DfaGenerator, Rng};
+///
+/// let mut rng = Rng::new(7);
+/// let dfa = DfaGenerator::new(10, 2).accept_density(0.2).generate(&mut rng);
+/// assert_eq!(dfa.states(), 10);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DfaGenerator {
+    states: u64,
+    alphabets: u64,
+    accept_density: f64,
+    reachable: bool,
+}
+
+impl DfaGenerator {
+    /// By default half of the states accept and every state is reachable from
+    /// the initial state 0.
+    pub fn new(states: u64, alphabets: u64) -> Self {
+        DfaGenerator {
+            states,
+            alphabets,
+            accept_density: 0.5,
+            reachable: true,
+        }
+    }
+
+    /// Probability for each state to be an accept state.
+    pub fn accept_density(mut self, density: f64) -> Self {
+        self.accept_density = density;
+        self
+    }
+
+    /// If true, a random spanning tree from the initial state is built first
+    /// so every state is reachable. Otherwise every transition is uniformly random.
+    pub fn reachable(mut self, reachable: bool) -> Self {
+        self.reachable = reachable;
+        self
+    }
+
+    /// Panics if states is 0, or if every state must be reachable
+    /// but there are several states and no alphabet.
+    pub fn generate(&self, rng: &mut Rng) -> Dfa {
+        assert!(self.states > 0, "a Dfa needs at least one state");
+        let n = self.states as usize;
+        let k = self.alphabets as usize;
+        let mut table: Vec<Vec<Option<u64>>> = vec![vec![None; k]; n];
+
+        if self.reachable && n > 1 {
+            assert!(k > 0, "states can not be reachable without alphabets");
+            // transitions of already connected states which are not set yet.
+            let mut free: Vec<(usize, usize)> = (0..k).map(|a| (0, a)).collect();
+            for q in 1..n {
+                let (p, a) = free.swap_remove(rng.below(free.len() as u64) as usize);
+                table[p][a] = Some(q as u64);
+                free.extend((0..k).map(|a| (q, a)));
+            }
+        }
+
+        let transition_function = table
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|next| next.unwrap_or_else(|| rng.below(self.states)))
+                    .collect()
+            })
+            .collect();
+        let accept_states = (0..self.states)
+            .filter(|_| rng.chance(self.accept_density))
+            .collect();
+        Dfa::new(
+            self.states,
+            self.alphabets,
+            transition_function,
+            0,
+            accept_states,
+        )
+        .unwrap()
+    }
+}
+
+/// Generator of random Nfas with state 0 as the only initial state.
+#[derive(Debug, Clone)]
+pub struct NfaGenerator {
+    states: u64,
+    alphabets: u64,
+    accept_density: f64,
+    transition_density: f64,
+    epsilon_density: f64,
+}
+
+impl NfaGenerator {
+    /// By default half of the states accept, each transition (p, a, q) exists
+    /// with probability 0.2 and each epsilon transition with probability 0.05.
+    pub fn new(states: u64, alphabets: u64) -> Self {
+        NfaGenerator {
+            states,
+            alphabets,
+            accept_density: 0.5,
+            transition_density: 0.2,
+            epsilon_density: 0.05,
+        }
+    }
+
+    /// Probability for each state to be an accept state.
+    pub fn accept_density(mut self, density: f64) -> Self {
+        self.accept_density = density;
+        self
+    }
+
+    /// Probability for each transition from a state on an alphabet to a state to exist.
+    pub fn transition_density(mut self, density: f64) -> Self {
+        self.transition_density = density;
+        self
+    }
+
+    /// Probability for each epsilon transition between two different states to exist.
+    pub fn epsilon_density(mut self, density: f64) -> Self {
+        self.epsilon_density = density;
+        self
+    }
+
+    /// Panics if states is 0.
+    pub fn generate(&self, rng: &mut Rng) -> Nfa {
+        assert!(self.states > 0, "an Nfa needs at least one initial state");
+        let transition_function = (0..self.states)
+            .map(|_| {
+                (0..self.alphabets)
+                    .map(|_| {
+                        (0..self.states)
+                            .filter(|_| rng.chance(self.transition_density))
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect();
+        let epsilon_transitions = (0..self.states)
+            .map(|p| {
+                (0..self.states)
+                    .filter(|&q| q != p && rng.chance(self.epsilon_density))
+                    .collect()
+            })
+            .collect();
+        let accept_states = (0..self.states)
+            .filter(|_| rng.chance(self.accept_density))
+            .collect();
+        Nfa::new(
+            self.states,
+            self.alphabets,
+            transition_function,
+            epsilon_transitions,
+            vec![0],
+            accept_states,
+        )
+        .unwrap()
+    }
+}
+
+/// Uniformly random input of length up to max_length.
+pub fn random_word(rng: &mut Rng, alphabets: u64, max_length: usize) -> Vec<u64> {
+    if alphabets == 0 {
+        return vec![];
+    }
+    let length = rng.below(max_length as u64 + 1);
+    (0..length).map(|_| rng.below(alphabets)).collect()
+}
+
+/// Random input of length up to max_length accepted by dfa,
+/// None if there is no such input.
+pub fn accepted_word(dfa: &Dfa, rng: &mut Rng, max_length: usize) -> Option<Vec<u64>> {
+    // exact[k][q] is true if an accept state is reached from q with exactly k alphabets.
+    let mut exact = vec![(0..dfa.states())
+        .map(|q| dfa.is_accept_state(q))
+        .collect::<Vec<bool>>()];
+    for _ in 0..max_length {
+        let last = exact.last().unwrap();
+        let next = (0..dfa.states())
+            .map(|q| (0..dfa.alphabets()).any(|a| last[dfa.next_state(q, a).unwrap() as usize]))
+            .collect();
+        exact.push(next);
+    }
+
+    let initial = dfa.initial_state() as usize;
+    let lengths: Vec<usize> = (0..=max_length).filter(|&l| exact[l][initial]).collect();
+    if lengths.is_empty() {
+        return None;
+    }
+    let length = lengths[rng.below(lengths.len() as u64) as usize];
+
+    // random walk staying on states from which the remaining length can be accepted.
+    let mut word = Vec::with_capacity(length);
+    let mut state = dfa.initial_state();
+    for remaining in (0..length).rev() {
+        let choices: Vec<u64> = (0..dfa.alphabets())
+            .filter(|&a| exact[remaining][dfa.next_state(state, a).unwrap() as usize])
+            .collect();
+        let a = choices[rng.below(choices.len() as u64) as usize];
+        word.push(a);
+        state = dfa.next_state(state, a).unwrap();
+    }
+    Some(word)
+}
+
+/// Random input of length up to max_length rejected by dfa,
+/// None if there is no such input.
+pub fn rejected_word(dfa: &Dfa, rng: &mut Rng, max_length: usize) -> Option<Vec<u64>> {
+    accepted_word(&dfa.complement(), rng, max_length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded() {
+        let a = DfaGenerator::new(20, 3).generate(&mut Rng::new(1));
+        let b = DfaGenerator::new(20, 3).generate(&mut Rng::new(1));
+        assert_eq!(a.equivalent(&b), Ok(()));
+        assert_eq!(a.to_text(), b.to_text());
+    }
+
+    #[test]
+    fn test_below_uniform() {
+        let mut rng = Rng::new(3);
+        // 2^64 is not a multiple of 3 * 2^62 + 1, so the modulo would favour
+        // results below 2^62 twice as often as the ones above it.
+        let n = 3 * (1 << 62) + 1;
+        let low = (0..3000).filter(|_| rng.below(n) < 1 << 62).count();
+        assert!(low > 800 && low < 1200, "{}", low);
+        assert!((0..100).all(|_| rng.below(1) == 0));
+    }
+
+    #[test]
+    fn test_seeds_differ() {
+        for &(a, b) in [(0, 1), (42, 43), (u64::MAX - 1, u64::MAX)].iter() {
+            assert_ne!(Rng::new(a).next_u64(), Rng::new(b).next_u64());
+        }
+    }
+
+    #[test]
+    fn test_reachable() {
+        let mut rng = Rng::new(3);
+        for _ in 0..20 {
+            let dfa = DfaGenerator::new(30, 2).generate(&mut rng);
+            assert!(dfa.reachable_states().iter().all(|&r| r));
+        }
+    }
+
+    #[test]
+    fn test_accepted_and_rejected_words() {
+        let mut rng = Rng::new(5);
+        for _ in 0..20 {
+            let dfa = DfaGenerator::new(8, 2)
+                .accept_density(0.3)
+                .generate(&mut rng);
+            if let Some(word) = accepted_word(&dfa, &mut rng, 10) {
+                assert!(dfa.is_language(word));
+            }
+            if let Some(word) = rejected_word(&dfa, &mut rng, 10) {
+                assert!(!dfa.is_language(word));
+            }
+        }
+        let all = DfaGenerator::new(1, 2)
+            .accept_density(1.0)
+            .generate(&mut rng);
+        assert_eq!(rejected_word(&all, &mut rng, 5), None);
+    }
+
+    #[test]
+    fn test_nfa_generator() {
+        let mut rng = Rng::new(13);
+        let nfa = NfaGenerator::new(10, 2)
+            .transition_density(1.0)
+            .accept_density(1.0)
+            .generate(&mut rng);
+        assert_eq!(nfa.next_states(3, 1).len(), 10);
+        assert!(nfa.is_language(random_word(&mut rng, 2, 10)));
+
+        let sparse = NfaGenerator::new(10, 2)
+            .transition_density(0.0)
+            .epsilon_density(0.0)
+            .generate(&mut rng);
+        assert!(!sparse.is_language(vec![0]));
+    }
+
+    // cross check algorithms of the crate against each other on random automata.
+    #[test]
+    fn test_cross_check() {
+        let mut rng = Rng::new(11);
+        for _ in 0..30 {
+            let a = DfaGenerator::new(6, 2).generate(&mut rng);
+            let b = DfaGenerator::new(5, 2).reachable(false).generate(&mut rng);
+            let (minimal, _) = a.minimize();
+            let union = a.union(&b).unwrap();
+            let intersection = a.intersection(&b).unwrap();
+            let compact = a.compact();
+            let regex = a.to_regex();
+            let nfa = Nfa::from(a.clone());
+
+            assert_eq!(minimal.equivalent(&a), Ok(()));
+            assert_eq!(minimal.minimize().0.states(), minimal.states());
+            assert_eq!(intersection.is_subset_of(&union), Ok(()));
+            for _ in 0..20 {
+                let word = random_word(&mut rng, 2, 8);
+                let in_a = a.is_language(word.clone());
+                let in_b = b.is_language(word.clone());
+                assert_eq!(union.is_language(word.clone()), in_a || in_b);
+                assert_eq!(intersection.is_language(word.clone()), in_a && in_b);
+                assert_eq!(compact.is_language(word.clone()), in_a);
+                assert_eq!(regex.is_match(&word), in_a);
+                assert_eq!(nfa.is_language(word.clone()), in_a);
+            }
+        }
+    }
+}
@@ -0,0 +1,395 @@
+use crate::dfa::Dfa;
+use std::fmt;
+
+/// Regular expression over u64 alphabets.
+/// Build it with the constructor functions, which apply basic simplifications
+/// like a | ∅ = a, a ε = a, a ∅ = ∅, (a*)* = a* and ∅* = ε* = ε.
+/// Variants built by hand are accepted too, an empty Concat is ε and an empty Alt is ∅.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Regex {
+    /// matches nothing.
+    Empty,
+    /// matches only the empty input.
+    Epsilon,
+    Symbol(u64),
+    Concat(Vec<Regex>),
+    Alt(Vec<Regex>),
+    Star(Box<Regex>),
+}
+
+impl Regex {
+    pub fn symbol(alphabet: u64) -> Regex {
+        Regex::Symbol(alphabet)
+    }
+
+    /// a followed by b.
+    pub fn concat(a: Regex, b: Regex) -> Regex {
+        match (a, b) {
+            (Regex::Empty, _) | (_, Regex::Empty) => Regex::Empty,
+            (Regex::Epsilon, r) | (r, Regex::Epsilon) => r,
+            (a, b) => {
+                let mut items = Vec::new();
+                for r in [a, b].iter().cloned() {
+                    match r {
+                        Regex::Concat(v) => items.extend(v),
+                        r => items.push(r),
+                    }
+                }
+                Regex::Concat(items)
+            }
+        }
+    }
+
+    /// a or b.
+    pub fn alt(a: Regex, b: Regex) -> Regex {
+        let mut items: Vec<Regex> = Vec::new();
+        for r in [a, b].iter().cloned() {
+            let v = match r {
+                Regex::Empty => vec![],
+                Regex::Alt(v) => v,
+                r => vec![r],
+            };
+            for r in v {
+                if !items.contains(&r) {
+                    items.push(r);
+                }
+            }
+        }
+        // ε | a* = a*
+        if items.contains(&Regex::Epsilon) && items.iter().any(|r| matches!(r, Regex::Star(_))) {
+            items.retain(|r| r != &Regex::Epsilon);
+        }
+        // keep a canonical order so that a | b and b | a are the same value.
+        items.sort();
+        match items.len() {
+            0 => Regex::Empty,
+            1 => items.pop().unwrap(),
+            _ => Regex::Alt(items),
+        }
+    }
+
+    /// zero or more repetitions of a.
+    pub fn star(a: Regex) -> Regex {
+        match a {
+            Regex::Empty | Regex::Epsilon => Regex::Epsilon,
+            // an empty Concat is ε and an empty Alt is ∅.
+            Regex::Concat(v) | Regex::Alt(v) if v.is_empty() => Regex::Epsilon,
+            Regex::Star(r) => Regex::Star(r),
+            // (ε | a)* = a*
+            Regex::Alt(v) if v.contains(&Regex::Epsilon) => {
+                let rest = v
+                    .into_iter()
+                    .filter(|r| r != &Regex::Epsilon)
+                    .fold(Regex::Empty, Regex::alt);
+                Regex::star(rest)
+            }
+            r => Regex::Star(Box::new(r)),
+        }
+    }
+
+    /// Whether the empty input matches.
+    pub fn is_nullable(&self) -> bool {
+        match self {
+            Regex::Empty | Regex::Symbol(_) => false,
+            Regex::Epsilon | Regex::Star(_) => true,
+            Regex::Concat(v) => v.iter().all(Regex::is_nullable),
+            Regex::Alt(v) => v.iter().any(Regex::is_nullable),
+        }
+    }
+
+    /// Brzozowski derivative, matching every input w such that alphabet w matches self.
+    pub fn derivative(&self, alphabet: u64) -> Regex {
+        match self {
+            Regex::Empty | Regex::Epsilon => Regex::Empty,
+            Regex::Symbol(a) => {
+                if *a == alphabet {
+                    Regex::Epsilon
+                } else {
+                    Regex::Empty
+                }
+            }
+            Regex::Concat(v) if v.is_empty() => Regex::Empty,
+            Regex::Concat(v) => {
+                let rest = v[1..].iter().cloned().fold(Regex::Epsilon, Regex::concat);
+                let first = Regex::concat(v[0].derivative(alphabet), rest.clone());
+                if v[0].is_nullable() {
+                    Regex::alt(first, rest.derivative(alphabet))
+                } else {
+                    first
+                }
+            }
+            Regex::Alt(v) => v
+                .iter()
+                .map(|r| r.derivative(alphabet))
+                .fold(Regex::Empty, Regex::alt),
+            Regex::Star(r) => Regex::concat(r.derivative(alphabet), self.clone()),
+        }
+    }
+
+    /// Whether the whole input matches.
+    pub fn is_match(&self, input: &[u64]) -> bool {
+        input
+            .iter()
+            .fold(self.clone(), |r, &a| r.derivative(a))
+            .is_nullable()
+    }
+
+    /// Precedence used to decide where parentheses are needed.
+    fn precedence(&self) -> u8 {
+        match self {
+            Regex::Alt(v) | Regex::Concat(v) if v.is_empty() => 3,
+            Regex::Alt(_) => 0,
+            Regex::Concat(_) => 1,
+            Regex::Star(_) => 2,
+            _ => 3,
+        }
+    }
+
+    fn fmt_operand(&self, f: &mut fmt::Formatter<'_>, precedence: u8) -> fmt::Result {
+        if self.precedence() < precedence {
+            write!(f, "({})", self)
+        } else {
+            write!(f, "{}", self)
+        }
+    }
+}
+
+/// Alphabets are written as numbers, concatenation separates them with a space,
+/// alternation uses | and repetition uses *.
+impl fmt::Display for Regex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Regex::Empty => write!(f, "∅"),
+            Regex::Epsilon => write!(f, "ε"),
+            Regex::Concat(v) if v.is_empty() => write!(f, "ε"),
+            Regex::Alt(v) if v.is_empty() => write!(f, "∅"),
+            Regex::Symbol(a) => write!(f, "{}", a),
+            Regex::Concat(v) => {
+                for (i, r) in v.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ")?;
+                    }
+                    r.fmt_operand(f, 2)?;
+                }
+                Ok(())
+            }
+            Regex::Alt(v) => {
+                for (i, r) in v.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " | ")?;
+                    }
+                    r.fmt_operand(f, 1)?;
+                }
+                Ok(())
+            }
+            Regex::Star(r) => {
+                r.fmt_operand(f, 3)?;
+                write!(f, "*")
+            }
+        }
+    }
+}
+
+impl Dfa {
+    /// Regular expression of the language of this Dfa, built by state elimination.
+    /// Unreachable and dead states are trimmed first, then states are eliminated
+    /// starting from the one with the fewest incoming and outgoing edges.
+    pub fn to_regex(&self) -> Regex {
+        let trimmed = self.trim();
+        let n = trimmed.states() as usize;
+        // node n is the new start and node n + 1 the new final node.
+        let start = n;
+        let end = n + 1;
+        let mut edges: Vec<Vec<Regex>> = vec![vec![Regex::Empty; n + 2]; n + 2];
+        edges[start][trimmed.initial_state() as usize] = Regex::Epsilon;
+        for (q, row) in edges.iter_mut().enumerate().take(n) {
+            if trimmed.is_accept_state(q as u64) {
+                row[end] = Regex::Epsilon;
+            }
+            for a in 0..trimmed.alphabets() {
+                if let Some(next) = trimmed.next_state(q as u64, a) {
+                    let r = std::mem::replace(&mut row[next as usize], Regex::Empty);
+                    row[next as usize] = Regex::alt(r, Regex::symbol(a));
+                }
+            }
+        }
+
+        let mut remaining: Vec<usize> = (0..n).collect();
+        while !remaining.is_empty() {
+            let degree = |q: usize| {
+                (0..n + 2)
+                    .filter(|&p| p != q)
+                    .map(|p| {
+                        (edges[p][q] != Regex::Empty) as usize
+                            + (edges[q][p] != Regex::Empty) as usize
+                    })
+                    .sum::<usize>()
+            };
+            let (i, &q) = remaining
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, &q)| degree(q))
+                .unwrap();
+            remaining.remove(i);
+
+            let self_loop = Regex::star(edges[q][q].clone());
+            let sources: Vec<usize> = (0..n + 2)
+                .filter(|&p| p != q && edges[p][q] != Regex::Empty)
+                .collect();
+            let targets: Vec<usize> = (0..n + 2)
+                .filter(|&r| r != q && edges[q][r] != Regex::Empty)
+                .collect();
+            for &p in &sources {
+                for &r in &targets {
+                    let through = Regex::concat(
+                        Regex::concat(edges[p][q].clone(), self_loop.clone()),
+                        edges[q][r].clone(),
+                    );
+                    let direct = std::mem::replace(&mut edges[p][r], Regex::Empty);
+                    edges[p][r] = Regex::alt(direct, through);
+                }
+            }
+            for row in edges.iter_mut() {
+                row[q] = Regex::Empty;
+            }
+            edges[q] = vec![Regex::Empty; n + 2];
+        }
+        edges[start][end].clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simplifications() {
+        let a = Regex::symbol(0);
+        assert_eq!(Regex::alt(a.clone(), Regex::Empty), a);
+        assert_eq!(Regex::alt(a.clone(), a.clone()), a);
+        assert_eq!(Regex::concat(a.clone(), Regex::Epsilon), a);
+        assert_eq!(Regex::concat(a.clone(), Regex::Empty), Regex::Empty);
+        assert_eq!(Regex::star(Regex::Empty), Regex::Epsilon);
+        assert_eq!(Regex::star(Regex::Epsilon), Regex::Epsilon);
+        assert_eq!(Regex::star(Regex::star(a.clone())), Regex::star(a.clone()));
+        assert_eq!(
+            Regex::star(Regex::alt(Regex::Epsilon, a.clone())),
+            Regex::star(a.clone())
+        );
+        assert_eq!(
+            Regex::alt(Regex::Epsilon, Regex::star(a.clone())),
+            Regex::star(a)
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let r = Regex::concat(
+            Regex::symbol(0),
+            Regex::star(Regex::alt(
+                Regex::symbol(1),
+                Regex::concat(Regex::symbol(2), Regex::symbol(3)),
+            )),
+        );
+        assert_eq!(r.to_string(), "0 (1 | 2 3)*");
+        assert_eq!(Regex::Empty.to_string(), "∅");
+    }
+
+    #[test]
+    fn test_to_regex() {
+        // 0 followed by one or more 1, state 3 is a sink.
+        let dfa = Dfa::new(
+            4,
+            2,
+            vec![vec![1, 3], vec![3, 2], vec![3, 2], vec![3, 3]],
+            0,
+            vec![2],
+        )
+        .unwrap();
+        assert_eq!(dfa.to_regex().to_string(), "0 1 1*");
+    }
+
+    #[test]
+    fn test_to_regex_parity() {
+        // even number of 1.
+        let dfa = Dfa::new(2, 2, vec![vec![0, 1], vec![1, 0]], 0, vec![0]).unwrap();
+        let r = dfa.to_regex();
+        assert_eq!(r.to_string(), "(0 | 1 0* 1)*");
+        assert!(r.is_nullable());
+    }
+
+    #[test]
+    fn test_is_match() {
+        let r = Regex::concat(
+            Regex::symbol(0),
+            Regex::star(Regex::alt(
+                Regex::symbol(1),
+                Regex::concat(Regex::symbol(2), Regex::symbol(3)),
+            )),
+        );
+        assert!(r.is_match(&[0]));
+        assert!(r.is_match(&[0, 1, 2, 3, 1]));
+        assert!(!r.is_match(&[0, 2]));
+        assert!(!r.is_match(&[]));
+    }
+
+    #[test]
+    fn test_empty_variants() {
+        let epsilon = Regex::Concat(vec![]);
+        let empty = Regex::Alt(vec![]);
+        assert!(epsilon.is_match(&[]));
+        assert!(!epsilon.is_match(&[0]));
+        assert!(!empty.is_match(&[]));
+        assert!(!empty.is_match(&[0]));
+        assert_eq!(Regex::star(Regex::Concat(vec![])).to_string(), "ε");
+        assert_eq!(Regex::star(Regex::Alt(vec![])).to_string(), "ε");
+        assert_eq!(
+            Regex::Concat(vec![Regex::symbol(0), empty.clone()]).to_string(),
+            "0 ∅"
+        );
+        assert!(!Regex::Concat(vec![Regex::symbol(0), empty]).is_match(&[0]));
+    }
+
+    #[test]
+    fn test_to_regex_same_language() {
+        // inputs over {0, 1, 2} whose second symbol from the end is 2.
+        let dfa = Dfa::new(
+            3,
+            3,
+            vec![vec![0, 0, 1], vec![2, 2, 1], vec![0, 0, 1]],
+            0,
+            vec![2],
+        )
+        .unwrap();
+        let r = dfa.to_regex();
+        let mut words = vec![vec![]];
+        for _ in 0..5 {
+            for word in &words {
+                assert_eq!(
+                    r.is_match(word),
+                    dfa.is_language(word.clone()),
+                    "{:?}",
+                    word
+                );
+            }
+            words = words
+                .iter()
+                .flat_map(|w| {
+                    (0..3).map(move |a| {
+                        let mut w = w.clone();
+                        w.push(a);
+                        w
+                    })
+                })
+                .collect();
+        }
+    }
+
+    #[test]
+    fn test_to_regex_empty_language() {
+        let dfa = Dfa::new(1, 2, vec![vec![0, 0]], 0, vec![]).unwrap();
+        assert_eq!(dfa.to_regex(), Regex::Empty);
+        let dfa = Dfa::new(1, 2, vec![vec![0, 0]], 0, vec![0]).unwrap();
+        assert_eq!(dfa.to_regex().to_string(), "(0 | 1)*");
+    }
+}
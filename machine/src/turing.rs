@@ -0,0 +1,865 @@
+use crate::dfa::{
+    check_duplicate, number, numbers, single_number, syntax_error, tokens, ParseError,
+};
+use std::collections::{HashSet, VecDeque};
+use std::error::Error;
+use std::fmt;
+use std::fmt::Write;
+
+/// Largest number of states times symbols of a table read by TuringMachine::from_text.
+pub const MAX_TABLE_SIZE: u64 = 1 << 24;
+
+/// Errors returned while building, reading or running a TuringMachine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TuringError {
+    /// transition_function does not have one row per state.
+    WrongRowCount { expected: u64, found: usize },
+    /// a row of transition_function does not have one column per symbol.
+    WrongColumnCount {
+        state: u64,
+        expected: u64,
+        found: usize,
+    },
+    /// a transition starts from a state which does not exist.
+    StateOutOfRange(u64),
+    /// a transition reads a symbol which does not exist.
+    SymbolOutOfRange(u64),
+    /// a transition goes to a state which does not exist.
+    TargetOutOfRange {
+        state: u64,
+        symbol: u64,
+        target: u64,
+    },
+    /// a transition from state writes a symbol which does not exist.
+    WriteSymbolOutOfRange { state: u64, symbol: u64 },
+    /// more than one transition is given from state on symbol.
+    DuplicateTransition { state: u64, symbol: u64 },
+    /// blank is not a valid symbol.
+    BlankOutOfRange(u64),
+    /// initial state is not a valid state.
+    InitialStateOutOfRange(u64),
+    /// one of accept states is not a valid state.
+    AcceptStateOutOfRange(u64),
+    /// one of reject states is not a valid state.
+    RejectStateOutOfRange(u64),
+    /// states times symbols is more than MAX_TABLE_SIZE.
+    TableTooLarge { states: u64, symbols: u64 },
+    /// input contains a symbol out of range at position.
+    InvalidInput { position: usize, symbol: u64 },
+    /// text is malformed.
+    Parse(ParseError),
+}
+
+impl fmt::Display for TuringError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TuringError::WrongRowCount { expected, found } => {
+                write!(f, "action table has {} rows, expected {}", found, expected)
+            }
+            TuringError::WrongColumnCount {
+                state,
+                expected,
+                found,
+            } => write!(
+                f,
+                "action table for state {} has {} columns, expected {}",
+                state, found, expected
+            ),
+            TuringError::StateOutOfRange(state) => write!(f, "state {} is out of range", state),
+            TuringError::SymbolOutOfRange(symbol) => {
+                write!(f, "symbol {} is out of range", symbol)
+            }
+            TuringError::TargetOutOfRange {
+                state,
+                symbol,
+                target,
+            } => write!(
+                f,
+                "transition from state {} on symbol {} goes to unknown state {}",
+                state, symbol, target
+            ),
+            TuringError::WriteSymbolOutOfRange { state, symbol } => write!(
+                f,
+                "transition from state {} writes unknown symbol {}",
+                state, symbol
+            ),
+            TuringError::DuplicateTransition { state, symbol } => write!(
+                f,
+                "more than one transition from state {} on symbol {}",
+                state, symbol
+            ),
+            TuringError::BlankOutOfRange(symbol) => {
+                write!(f, "blank symbol {} is out of range", symbol)
+            }
+            TuringError::InitialStateOutOfRange(state) => {
+                write!(f, "initial state {} is out of range", state)
+            }
+            TuringError::AcceptStateOutOfRange(state) => {
+                write!(f, "accept state {} is out of range", state)
+            }
+            TuringError::RejectStateOutOfRange(state) => {
+                write!(f, "reject state {} is out of range", state)
+            }
+            TuringError::TableTooLarge { states, symbols } => write!(
+                f,
+                "table of {} states and {} symbols is too large",
+                states, symbols
+            ),
+            TuringError::InvalidInput { position, symbol } => write!(
+                f,
+                "invalid symbol {} at position {} of input",
+                symbol, position
+            ),
+            TuringError::Parse(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for TuringError {}
+
+impl From<ParseError> for TuringError {
+    fn from(err: ParseError) -> Self {
+        TuringError::Parse(err)
+    }
+}
+
+/// Direction in which the head moves after writing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Move {
+    Left,
+    Right,
+    Stay,
+}
+
+/// What the machine does on a state and a read symbol:
+/// write a symbol, move the head and go to the next state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Action {
+    pub write: u64,
+    pub movement: Move,
+    pub next: u64,
+}
+
+impl Action {
+    pub fn new(write: u64, movement: Move, next: u64) -> Self {
+        Action {
+            write,
+            movement,
+            next,
+        }
+    }
+}
+
+/// Result of running a Turing machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// halted in one of accept states.
+    Accept,
+    /// halted in one of reject states.
+    Reject,
+    /// halted in a state which is neither accepting nor rejecting.
+    Halt,
+    /// still running after the step limit.
+    StepLimitExceeded,
+}
+
+/// Single tape deterministic Turing machine.
+/// States and tape symbols are u64 integers as in Dfa, symbols include blank.
+/// transition_function[q][s] is the action on state q reading symbol s,
+/// and the machine halts when it is None.
+#[derive(Debug, Clone)]
+pub struct TuringMachine {
+    states: u64,
+    symbols: u64,
+    blank: u64,
+    transition_function: Vec<Vec<Option<Action>>>,
+    initial_state: u64,
+    accept_states: HashSet<u64>,
+    reject_states: HashSet<u64>,
+}
+
+impl TuringMachine {
+    pub fn new(
+        states: u64,
+        symbols: u64,
+        blank: u64,
+        transition_function: Vec<Vec<Option<Action>>>,
+        initial_state: u64,
+        accept_states: Vec<u64>,
+        reject_states: Vec<u64>,
+    ) -> Result<Self, TuringError> {
+        if transition_function.len() != states as usize {
+            return Err(TuringError::WrongRowCount {
+                expected: states,
+                found: transition_function.len(),
+            });
+        }
+        if blank >= symbols {
+            return Err(TuringError::BlankOutOfRange(blank));
+        }
+        for (q, row) in transition_function.iter().enumerate() {
+            if row.len() != symbols as usize {
+                return Err(TuringError::WrongColumnCount {
+                    state: q as u64,
+                    expected: symbols,
+                    found: row.len(),
+                });
+            }
+            for (s, action) in row.iter().enumerate() {
+                let action = match action {
+                    Some(action) => action,
+                    None => continue,
+                };
+                if action.next >= states {
+                    return Err(TuringError::TargetOutOfRange {
+                        state: q as u64,
+                        symbol: s as u64,
+                        target: action.next,
+                    });
+                }
+                if action.write >= symbols {
+                    return Err(TuringError::WriteSymbolOutOfRange {
+                        state: q as u64,
+                        symbol: action.write,
+                    });
+                }
+            }
+        }
+        if initial_state >= states {
+            return Err(TuringError::InitialStateOutOfRange(initial_state));
+        }
+        if let Some(&q) = accept_states.iter().find(|&&q| q >= states) {
+            return Err(TuringError::AcceptStateOutOfRange(q));
+        }
+        if let Some(&q) = reject_states.iter().find(|&&q| q >= states) {
+            return Err(TuringError::RejectStateOutOfRange(q));
+        }
+
+        Ok(TuringMachine {
+            states,
+            symbols,
+            blank,
+            transition_function,
+            initial_state,
+            accept_states: accept_states.into_iter().collect(),
+            reject_states: reject_states.into_iter().collect(),
+        })
+    }
+
+    pub fn states(&self) -> u64 {
+        self.states
+    }
+
+    pub fn symbols(&self) -> u64 {
+        self.symbols
+    }
+
+    pub fn blank(&self) -> u64 {
+        self.blank
+    }
+
+    pub fn action(&self, state: u64, symbol: u64) -> Option<Action> {
+        *self
+            .transition_function
+            .get(state as usize)?
+            .get(symbol as usize)?
+    }
+
+    /// Start a run with input written from position 0 and the head on it.
+    pub fn runner(&self, input: &[u64]) -> Result<TuringRunner<'_>, TuringError> {
+        if let Some(position) = input.iter().position(|&s| s >= self.symbols) {
+            return Err(TuringError::InvalidInput {
+                position,
+                symbol: input[position],
+            });
+        }
+        let mut tape: VecDeque<u64> = input.iter().copied().collect();
+        if tape.is_empty() {
+            tape.push_back(self.blank);
+        }
+        Ok(TuringRunner {
+            machine: self,
+            state: self.initial_state,
+            tape,
+            offset: 0,
+            head: 0,
+            steps: 0,
+        })
+    }
+
+    /// Run on input for at most max_steps steps.
+    pub fn run(&self, input: &[u64], max_steps: usize) -> Result<Outcome, TuringError> {
+        Ok(self.runner(input)?.run(max_steps))
+    }
+
+    /// Run on input for at most max_steps steps, with a snapshot of the
+    /// initial configuration and of the configuration after each step.
+    pub fn trace(
+        &self,
+        input: &[u64],
+        max_steps: usize,
+    ) -> Result<(Outcome, Vec<Snapshot>), TuringError> {
+        let mut runner = self.runner(input)?;
+        let mut snapshots = vec![runner.snapshot()];
+        for _ in 0..max_steps {
+            if !runner.step() {
+                return Ok((runner.outcome(), snapshots));
+            }
+            snapshots.push(runner.snapshot());
+        }
+        let outcome = if runner.halted() {
+            runner.outcome()
+        } else {
+            Outcome::StepLimitExceeded
+        };
+        Ok((outcome, snapshots))
+    }
+
+    /// Read a Turing machine from the text format.
+    ///
+    /// ```text
+    /// # flips every bit then accepts on the first blank.
+    /// states 2
+    /// symbols 3
+    /// blank 0
+    /// initial 0
+    /// accept 1
+    /// reject
+    /// transitions
+    /// 0 1 -> 0 2 R
+    /// 0 2 -> 0 1 R
+    /// 0 0 -> 1 0 S
+    /// ```
+    ///
+    /// Keywords are the same as Dfa::from_text with symbols instead of alphabets.
+    /// blank defaults to 0 and accept and reject take zero or more states.
+    /// Every line after transitions reads "state symbol -> next write move",
+    /// where move is L, R or S, and missing transitions halt.
+    /// Errors in a transition are reported at its line as a syntax error,
+    /// and the table can have at most MAX_TABLE_SIZE entries.
+    pub fn from_text(text: &str) -> Result<TuringMachine, TuringError> {
+        let mut states = None;
+        let mut symbols = None;
+        let mut blank = None;
+        let mut initial_state = None;
+        let mut accept_states = None;
+        let mut reject_states = None;
+        let mut seen = HashSet::new();
+        // line and column of each transition, to report errors found after reading them all.
+        let mut transitions: Option<Vec<TextTransition>> = None;
+
+        for (i, raw) in text.lines().enumerate() {
+            let line = i + 1;
+            let content = match raw.find('#') {
+                Some(end) => &raw[..end],
+                None => raw,
+            };
+            let mut tokens = tokens(content).peekable();
+            let (column, keyword) = match tokens.peek() {
+                Some(&token) => token,
+                None => continue,
+            };
+
+            if let Some(transitions) = transitions.as_mut() {
+                let (state, symbol, action) = transition(line, tokens.collect())?;
+                transitions.push((line, column, state, symbol, action));
+                continue;
+            }
+            tokens.next();
+            check_duplicate(&mut seen, line, column, keyword)?;
+            match keyword {
+                "states" => states = Some(single_number(line, column, keyword, tokens)?),
+                "symbols" => symbols = Some(single_number(line, column, keyword, tokens)?),
+                "blank" => blank = Some(single_number(line, column, keyword, tokens)?),
+                "initial" => initial_state = Some(single_number(line, column, keyword, tokens)?),
+                "accept" => accept_states = Some(numbers(line, tokens)?),
+                "reject" => reject_states = Some(numbers(line, tokens)?),
+                "transitions" => {
+                    if let Some((column, token)) = tokens.next() {
+                        return syntax_error(
+                            line,
+                            column,
+                            format!("unexpected '{}' after transitions", token),
+                        )
+                        .map_err(TuringError::Parse);
+                    }
+                    transitions = Some(Vec::new());
+                }
+                _ => {
+                    return syntax_error(line, column, format!("unknown keyword '{}'", keyword))
+                        .map_err(TuringError::Parse);
+                }
+            }
+        }
+
+        let end = text.lines().count() + 1;
+        let missing = |name: &str| ParseError::Syntax {
+            line: end,
+            column: 1,
+            message: format!("missing {}", name),
+        };
+        let states = states.ok_or_else(|| missing("states"))?;
+        let symbols = symbols.ok_or_else(|| missing("symbols"))?;
+        let initial_state = initial_state.ok_or_else(|| missing("initial"))?;
+        let transitions = transitions.ok_or_else(|| missing("transitions"))?;
+
+        match states.checked_mul(symbols) {
+            Some(size) if size <= MAX_TABLE_SIZE => {}
+            _ => return Err(TuringError::TableTooLarge { states, symbols }),
+        }
+
+        let mut transition_function = vec![vec![None; symbols as usize]; states as usize];
+        for (line, column, state, symbol, action) in transitions {
+            let error = if state >= states {
+                Some(TuringError::StateOutOfRange(state))
+            } else if action.next >= states {
+                Some(TuringError::TargetOutOfRange {
+                    state,
+                    symbol,
+                    target: action.next,
+                })
+            } else if symbol >= symbols {
+                Some(TuringError::SymbolOutOfRange(symbol))
+            } else if action.write >= symbols {
+                Some(TuringError::WriteSymbolOutOfRange {
+                    state,
+                    symbol: action.write,
+                })
+            } else if transition_function[state as usize][symbol as usize].is_some() {
+                Some(TuringError::DuplicateTransition { state, symbol })
+            } else {
+                None
+            };
+            if let Some(error) = error {
+                return syntax_error(line, column, error.to_string()).map_err(TuringError::Parse);
+            }
+            transition_function[state as usize][symbol as usize] = Some(action);
+        }
+        TuringMachine::new(
+            states,
+            symbols,
+            blank.unwrap_or(0),
+            transition_function,
+            initial_state,
+            accept_states.unwrap_or_default(),
+            reject_states.unwrap_or_default(),
+        )
+    }
+
+    /// Write this Turing machine in the text format read by from_text.
+    pub fn to_text(&self) -> String {
+        let sorted = |set: &HashSet<u64>| {
+            let mut states: Vec<u64> = set.iter().copied().collect();
+            states.sort_unstable();
+            states
+        };
+        let mut out = String::new();
+        writeln!(out, "states {}", self.states).unwrap();
+        writeln!(out, "symbols {}", self.symbols).unwrap();
+        writeln!(out, "blank {}", self.blank).unwrap();
+        writeln!(out, "initial {}", self.initial_state).unwrap();
+        for (keyword, set) in [
+            ("accept", &self.accept_states),
+            ("reject", &self.reject_states),
+        ]
+        .iter()
+        {
+            out.push_str(keyword);
+            for q in sorted(set) {
+                write!(out, " {}", q).unwrap();
+            }
+            out.push('\n');
+        }
+        out.push_str("transitions\n");
+        for (q, row) in self.transition_function.iter().enumerate() {
+            for (s, action) in row.iter().enumerate() {
+                if let Some(action) = action {
+                    let movement = match action.movement {
+                        Move::Left => "L",
+                        Move::Right => "R",
+                        Move::Stay => "S",
+                    };
+                    writeln!(
+                        out,
+                        "{} {} -> {} {} {}",
+                        q, s, action.next, action.write, movement
+                    )
+                    .unwrap();
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Line, column, state, symbol and action of a transition read from text.
+type TextTransition = (usize, usize, u64, u64, Action);
+
+/// Transition line "state symbol -> next write move".
+fn transition(line: usize, tokens: Vec<(usize, &str)>) -> Result<(u64, u64, Action), ParseError> {
+    if tokens.len() != 6 || tokens[2].1 != "->" {
+        return syntax_error(
+            line,
+            tokens[0].0,
+            "expected 'state symbol -> next write move'".to_string(),
+        );
+    }
+    let movement = match tokens[5].1 {
+        "L" => Move::Left,
+        "R" => Move::Right,
+        "S" => Move::Stay,
+        token => {
+            return syntax_error(
+                line,
+                tokens[5].0,
+                format!("expected L, R or S, found '{}'", token),
+            )
+        }
+    };
+    Ok((
+        number(line, tokens[0].0, tokens[0].1)?,
+        number(line, tokens[1].0, tokens[1].1)?,
+        Action::new(
+            number(line, tokens[4].0, tokens[4].1)?,
+            movement,
+            number(line, tokens[3].0, tokens[3].1)?,
+        ),
+    ))
+}
+
+/// Step by step execution of a TuringMachine.
+/// The tape is infinite in both directions and grows with blanks on demand.
+#[derive(Debug, Clone)]
+pub struct TuringRunner<'a> {
+    machine: &'a TuringMachine,
+    state: u64,
+    tape: VecDeque<u64>,
+    // position of tape[0].
+    offset: i64,
+    head: i64,
+    steps: usize,
+}
+
+impl<'a> TuringRunner<'a> {
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    /// Position of the head, the input starts at 0.
+    pub fn head(&self) -> i64 {
+        self.head
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    fn index(&self) -> usize {
+        (self.head - self.offset) as usize
+    }
+
+    /// Whether no action is defined for the current state and symbol.
+    pub fn halted(&self) -> bool {
+        let symbol = self.tape[self.index()];
+        self.machine.action(self.state, symbol).is_none()
+    }
+
+    /// Take one step, false if the machine has halted.
+    pub fn step(&mut self) -> bool {
+        let index = self.index();
+        let action = match self.machine.action(self.state, self.tape[index]) {
+            Some(action) => action,
+            None => return false,
+        };
+        self.tape[index] = action.write;
+        self.state = action.next;
+        match action.movement {
+            Move::Left => {
+                self.head -= 1;
+                if self.head < self.offset {
+                    self.tape.push_front(self.machine.blank);
+                    self.offset -= 1;
+                }
+            }
+            Move::Right => {
+                self.head += 1;
+                if self.index() == self.tape.len() {
+                    self.tape.push_back(self.machine.blank);
+                }
+            }
+            Move::Stay => {}
+        }
+        self.steps += 1;
+        true
+    }
+
+    /// Step until the machine halts or max_steps more steps are taken.
+    pub fn run(&mut self, max_steps: usize) -> Outcome {
+        for _ in 0..max_steps {
+            if !self.step() {
+                return self.outcome();
+            }
+        }
+        if self.halted() {
+            self.outcome()
+        } else {
+            Outcome::StepLimitExceeded
+        }
+    }
+
+    /// Outcome if the machine stopped now.
+    fn outcome(&self) -> Outcome {
+        if self.machine.accept_states.contains(&self.state) {
+            Outcome::Accept
+        } else if self.machine.reject_states.contains(&self.state) {
+            Outcome::Reject
+        } else {
+            Outcome::Halt
+        }
+    }
+
+    /// Copy of the current configuration.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            state: self.state,
+            head: self.head,
+            offset: self.offset,
+            tape: self.tape.iter().copied().collect(),
+        }
+    }
+}
+
+/// Configuration of a Turing machine at some step, for debugging.
+/// tape covers every cell visited so far, tape[0] is at position offset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    pub state: u64,
+    pub head: i64,
+    pub offset: i64,
+    pub tape: Vec<u64>,
+}
+
+/// Written as the state followed by the tape, with the head cell in brackets,
+/// for example "q1: 0 [1] 1".
+impl fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "q{}:", self.state)?;
+        for (i, s) in self.tape.iter().enumerate() {
+            if i as i64 + self.offset == self.head {
+                write!(f, " [{}]", s)?;
+            } else {
+                write!(f, " {}", s)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FLIP: &str = "# flips every bit then accepts on the first blank.
+states 2
+symbols 3
+blank 0
+initial 0
+accept 1
+transitions
+0 1 -> 0 2 R
+0 2 -> 0 1 R
+0 0 -> 1 0 S
+";
+
+    // accepts inputs of the form 1^n 2^n by crossing out pairs with 3.
+    fn equal_counts() -> TuringMachine {
+        let text = "states 6
+symbols 4
+initial 0
+accept 4
+reject 5
+transitions
+0 0 -> 4 0 S
+0 3 -> 4 3 S
+0 1 -> 1 3 R
+0 2 -> 5 2 S
+1 1 -> 1 1 R
+1 3 -> 1 3 R
+1 2 -> 2 3 L
+1 0 -> 5 0 S
+2 1 -> 2 1 L
+2 3 -> 2 3 L
+2 0 -> 3 0 R
+3 3 -> 3 3 R
+3 1 -> 1 3 R
+3 0 -> 4 0 S
+3 2 -> 5 2 S
+";
+        TuringMachine::from_text(text).unwrap()
+    }
+
+    #[test]
+    fn test_run() {
+        let tm = equal_counts();
+        assert_eq!(tm.run(&[], 1000), Ok(Outcome::Accept));
+        assert_eq!(tm.run(&[1, 1, 2, 2], 1000), Ok(Outcome::Accept));
+        assert_eq!(tm.run(&[1, 1, 2], 1000), Ok(Outcome::Reject));
+        assert_eq!(tm.run(&[1, 2, 2], 1000), Ok(Outcome::Reject));
+        assert_eq!(tm.run(&[2, 1], 1000), Ok(Outcome::Reject));
+        assert_eq!(tm.run(&[1, 1, 2, 2], 3), Ok(Outcome::StepLimitExceeded));
+        assert_eq!(
+            tm.run(&[4], 10),
+            Err(TuringError::InvalidInput {
+                position: 0,
+                symbol: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        let tm = TuringMachine::from_text(FLIP).unwrap();
+        let (outcome, snapshots) = tm.trace(&[1, 2], 10).unwrap();
+        assert_eq!(outcome, Outcome::Accept);
+        let snapshots: Vec<String> = snapshots.iter().map(|s| s.to_string()).collect();
+        assert_eq!(
+            snapshots,
+            ["q0: [1] 2", "q0: 2 [2]", "q0: 2 1 [0]", "q1: 2 1 [0]"]
+        );
+    }
+
+    #[test]
+    fn test_halt_and_left_growth() {
+        // moves left forever writing 1, or halts in a plain state on 1.
+        let tm = TuringMachine::new(
+            2,
+            2,
+            0,
+            vec![
+                vec![
+                    Some(Action::new(1, Move::Left, 0)),
+                    Some(Action::new(1, Move::Stay, 1)),
+                ],
+                vec![None, None],
+            ],
+            0,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(tm.run(&[1], 10), Ok(Outcome::Halt));
+        let mut runner = tm.runner(&[]).unwrap();
+        assert_eq!(runner.run(3), Outcome::StepLimitExceeded);
+        assert_eq!(runner.head(), -3);
+        assert_eq!(runner.snapshot().to_string(), "q0: [0] 1 1 1");
+    }
+
+    #[test]
+    fn test_new_errors() {
+        let row = || vec![Some(Action::new(1, Move::Right, 0)), None];
+        let new = |blank, table, accept, reject| {
+            TuringMachine::new(1, 2, blank, table, 0, accept, reject).unwrap_err()
+        };
+        assert_eq!(
+            new(2, vec![row()], vec![], vec![]),
+            TuringError::BlankOutOfRange(2)
+        );
+        assert_eq!(
+            new(
+                0,
+                vec![vec![None, Some(Action::new(2, Move::Left, 0))]],
+                vec![],
+                vec![]
+            ),
+            TuringError::WriteSymbolOutOfRange {
+                state: 0,
+                symbol: 2
+            }
+        );
+        assert_eq!(
+            new(0, vec![row()], vec![1], vec![]),
+            TuringError::AcceptStateOutOfRange(1)
+        );
+        assert_eq!(
+            new(0, vec![row()], vec![], vec![1]),
+            TuringError::RejectStateOutOfRange(1)
+        );
+        assert_eq!(
+            new(0, vec![vec![None]], vec![], vec![]),
+            TuringError::WrongColumnCount {
+                state: 0,
+                expected: 2,
+                found: 1
+            }
+        );
+        assert_eq!(
+            new(0, vec![], vec![], vec![]),
+            TuringError::WrongRowCount {
+                expected: 1,
+                found: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_text_too_large() {
+        let text = "states 18446744073709551615\nsymbols 2\ninitial 0\ntransitions\n";
+        assert_eq!(
+            TuringMachine::from_text(text).unwrap_err(),
+            TuringError::TableTooLarge {
+                states: 18446744073709551615,
+                symbols: 2
+            }
+        );
+        let text = "states 100000\nsymbols 100000\ninitial 0\ntransitions\n";
+        assert!(TuringMachine::from_text(text).is_err());
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let tm = equal_counts();
+        let parsed = TuringMachine::from_text(&tm.to_text()).unwrap();
+        assert_eq!(parsed.to_text(), tm.to_text());
+        assert_eq!(parsed.run(&[1, 2], 100), Ok(Outcome::Accept));
+    }
+
+    #[test]
+    fn test_text_errors() {
+        let text = "states 1\nsymbols 2\ninitial 0\ntransitions\n0 1 -> 0 1 X\n";
+        assert_eq!(
+            TuringMachine::from_text(text).unwrap_err(),
+            TuringError::Parse(ParseError::Syntax {
+                line: 5,
+                column: 12,
+                message: "expected L, R or S, found 'X'".to_string()
+            })
+        );
+        let text = "states 1\nsymbols 2\ninitial 0\ntransitions\n0 1 -> 0 1 R\n0 1 -> 0 0 L\n";
+        assert_eq!(
+            TuringMachine::from_text(text).unwrap_err(),
+            TuringError::Parse(ParseError::Syntax {
+                line: 6,
+                column: 1,
+                message: "more than one transition from state 0 on symbol 1".to_string()
+            })
+        );
+        let text = "states 1\nsymbols 2\naccept 0\naccept\ninitial 0\ntransitions\n";
+        assert_eq!(
+            TuringMachine::from_text(text).unwrap_err(),
+            TuringError::Parse(ParseError::Syntax {
+                line: 4,
+                column: 1,
+                message: "duplicate 'accept'".to_string()
+            })
+        );
+        let text = "states 1\nsymbols 2\ninitial 0\ntransitions\n0 1 -> 1 1 R\n";
+        assert_eq!(
+            TuringMachine::from_text(text).unwrap_err(),
+            TuringError::Parse(ParseError::Syntax {
+                line: 5,
+                column: 1,
+                message: "transition from state 0 on symbol 1 goes to unknown state 1".to_string()
+            })
+        );
+    }
+}